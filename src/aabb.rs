@@ -0,0 +1,35 @@
+use crate::vec3::Vec3;
+
+// Caja alineada a los ejes (espacio mundo)
+#[derive(Copy, Clone, Debug)]
+pub struct Aabb { pub min: Vec3, pub max: Vec3 }
+
+impl Aabb {
+    pub const EMPTY: Aabb = Aabb {
+        min: Vec3 { x: f32::INFINITY, y: f32::INFINITY, z: f32::INFINITY },
+        max: Vec3 { x: f32::NEG_INFINITY, y: f32::NEG_INFINITY, z: f32::NEG_INFINITY },
+    };
+
+    pub fn new(min: Vec3, max: Vec3) -> Self { Self { min, max } }
+    pub fn from_points(pts: &[Vec3]) -> Self { pts.iter().fold(Self::EMPTY, |b, &p| b.grow(p)) }
+    pub fn grow(self, p: Vec3) -> Self { Self::new(self.min.min(p), self.max.max(p)) }
+    pub fn union(self, o: Aabb) -> Self { Self::new(self.min.min(o.min), self.max.max(o.max)) }
+    pub fn centroid(&self) -> Vec3 { (self.min + self.max) * 0.5 }
+    pub fn is_empty(&self) -> bool { self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z }
+    pub fn surface_area(&self) -> f32 {
+        if self.is_empty() { return 0.0; }
+        let d = self.max - self.min;
+        2.0 * (d.x*d.y + d.y*d.z + d.z*d.x)
+    }
+
+    // Slab test con la inversa de la dirección precalculada; devuelve t de entrada
+    #[inline]
+    pub fn hit(&self, o: Vec3, inv_d: Vec3, tmin: f32, tmax: f32) -> Option<f32> {
+        let tx0 = (self.min.x - o.x) * inv_d.x; let tx1 = (self.max.x - o.x) * inv_d.x;
+        let ty0 = (self.min.y - o.y) * inv_d.y; let ty1 = (self.max.y - o.y) * inv_d.y;
+        let tz0 = (self.min.z - o.z) * inv_d.z; let tz1 = (self.max.z - o.z) * inv_d.z;
+        let t0 = tx0.min(tx1).max(ty0.min(ty1)).max(tz0.min(tz1)).max(tmin);
+        let t1 = tx0.max(tx1).min(ty0.max(ty1)).min(tz0.max(tz1)).min(tmax);
+        if t0 <= t1 { Some(t0) } else { None }
+    }
+}
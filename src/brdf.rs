@@ -0,0 +1,91 @@
+use crate::vec3::Vec3;
+use crate::scene::Material;
+use crate::sampling::orthonormal_basis;
+
+// BRDF metálico/rugosidad: difuso de Lambert + especular de microfacetas GGX
+// (distribución GGX, sombreado Smith height-correlated, Fresnel-Schlick).
+// Los valores devueltos van multiplicados por π: así una luz de radiancia `L`
+// sobre un difuso blanco da `L * n·l`, igual que en el modelo Phong.
+
+use std::f32::consts::PI;
+
+// Reflectancia a incidencia normal de un dieléctrico con índice `ior` (en aire)
+pub fn f0_dielectric(ior: f32) -> f32 { ((ior - 1.0) / (ior + 1.0)).powi(2) }
+
+// F0 por canal: los dieléctricos usan su ior; los conductores, el color base
+pub fn f0(base: Vec3, metallic: f32, ior: f32) -> Vec3 {
+    let d = f0_dielectric(ior);
+    Vec3::new(d, d, d) * (1.0 - metallic) + base * metallic
+}
+
+// Fresnel exacto (luz no polarizada) de una interfaz dieléctrica: medio n1 -> medio n2,
+// `cos_i` respecto de la normal del lado incidente. 1.0 en reflexión total interna.
+pub fn fresnel_dielectric(cos_i: f32, n1: f32, n2: f32) -> f32 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let eta = n1 / n2;
+    let sin_t2 = eta * eta * (1.0 - cos_i * cos_i);
+    if sin_t2 >= 1.0 { return 1.0; }
+    let cos_t = (1.0 - sin_t2).sqrt();
+    let rs = (n1 * cos_i - n2 * cos_t) / (n1 * cos_i + n2 * cos_t);
+    let rp = (n2 * cos_i - n1 * cos_t) / (n2 * cos_i + n1 * cos_t);
+    0.5 * (rs * rs + rp * rp)
+}
+
+pub fn fresnel_schlick(cos: f32, f0: Vec3) -> Vec3 {
+    let k = (1.0 - cos.clamp(0.0, 1.0)).powi(5);
+    f0 + (Vec3::ONE - f0) * k
+}
+
+// Fresnel para la reflexión del entorno: en superficies rugosas el borde brilla menos
+pub fn fresnel_schlick_roughness(cos: f32, f0: Vec3, roughness: f32) -> Vec3 {
+    let k = (1.0 - cos.clamp(0.0, 1.0)).powi(5);
+    let g = 1.0 - roughness;
+    f0 + (f0.max(Vec3::new(g, g, g)) - f0) * k
+}
+
+fn ggx_d(nh: f32, a2: f32) -> f32 {
+    let d = nh * nh * (a2 - 1.0) + 1.0;
+    a2 / (PI * d * d).max(1e-12)
+}
+
+// V = G / (4 n·l n·v), Smith height-correlated
+fn smith_v(nl: f32, nv: f32, a2: f32) -> f32 {
+    let gv = nl * (nv * nv * (1.0 - a2) + a2).sqrt();
+    let gl = nv * (nl * nl * (1.0 - a2) + a2).sqrt();
+    0.5 / (gv + gl).max(1e-12)
+}
+
+// π·f(l, v) para n, v, l unitarios con n·l > 0; `base` es el color de la textura en el punto.
+// La parte transparente de un dieléctrico no tiene lóbulo difuso (la luz pasa a través).
+pub fn eval(m: &Material, base: Vec3, n: Vec3, v: Vec3, l: Vec3) -> Vec3 {
+    let nl = n.dot(l).max(0.0);
+    let nv = n.dot(v).max(1e-4);
+    let h = (l + v).normalize();
+    let nh = n.dot(h).max(0.0);
+    let a = m.roughness.clamp(0.02, 1.0).powi(2);
+    let a2 = a * a;
+
+    let f = fresnel_schlick(v.dot(h), f0(base, m.metallic, m.ior));
+    let spec = f * (ggx_d(nh, a2) * smith_v(nl, nv, a2) * PI);
+    let kd = (Vec3::ONE - f) * ((1.0 - m.metallic) * (1.0 - m.transparency));
+    base.hadamard(kd) + spec
+}
+
+// Normal de microfaceta muestreada con densidad D(h)·(n·h) alrededor de `n`, con `u` en [0,1)².
+// Reflejar/refractar respecto de ella da los lóbulos del metal cepillado y el vidrio esmerilado.
+pub fn sample_ggx_normal(n: Vec3, roughness: f32, u: (f32, f32)) -> Vec3 {
+    let a = roughness.clamp(0.02, 1.0).powi(2);
+    let cos_t = ((1.0 - u.0) / (1.0 + (a * a - 1.0) * u.0)).max(0.0).sqrt();
+    let sin_t = (1.0 - cos_t * cos_t).max(0.0).sqrt();
+    let phi = 2.0 * PI * u.1;
+    let (t, b) = orthonormal_basis(n);
+    (t * (sin_t * phi.cos()) + b * (sin_t * phi.sin()) + n * cos_t).normalize()
+}
+
+// Densidad (por ángulo sólido de `l`) de reflejar `v` respecto de una normal de `sample_ggx_normal`
+pub fn ggx_pdf(n: Vec3, v: Vec3, l: Vec3, roughness: f32) -> f32 {
+    let h = (l + v).normalize();
+    let a = roughness.clamp(0.02, 1.0).powi(2);
+    let nh = n.dot(h).max(0.0);
+    ggx_d(nh, a * a) * nh / (4.0 * v.dot(h).max(1e-4))
+}
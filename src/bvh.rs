@@ -0,0 +1,291 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::aabb::Aabb;
+use crate::geometry::{Hit, Hittable};
+use crate::ray::Ray;
+use crate::vec3::Vec3;
+
+// BVH construido con SAH (binning) sobre los bounds de cada primitiva
+// (objetos de la escena en mundo, o triángulos de una malla en su espacio local).
+// Las primitivas sin bounds (p.ej. planos infinitos) se prueban aparte, siempre.
+
+const BINS: usize = 16;
+const MAX_LEAF: usize = 2;   // hojas a partir de aquí si SAH no mejora
+const HARD_LEAF: usize = 8;  // por encima de esto se parte aunque SAH no mejore
+const MAX_DEPTH: usize = 60; // cabe en la pila fija del recorrido
+const STACK: usize = 64;
+
+#[derive(Copy, Clone)]
+struct Node {
+    bounds: Aabb,
+    first: u32, // hoja: primer índice en `indices`; interior: hijo izquierdo (derecho = first+1)
+    count: u32, // 0 => nodo interior
+}
+
+// Contadores del hilo actual (rayos, nodos, primitivas): cada rayo suma aquí, sin atómicos,
+// y `BvhStats::flush` los vuelca a los totales una vez por tile
+thread_local! { static LOCAL: Cell<[u64; 3]> = const { Cell::new([0; 3]) }; }
+
+#[derive(Default)]
+pub struct BvhStats {
+    pub rays: AtomicU64,
+    pub nodes_visited: AtomicU64,
+    pub prims_tested: AtomicU64,
+}
+
+impl BvhStats {
+    pub fn reset(&self) {
+        LOCAL.set([0; 3]);
+        self.rays.store(0, Ordering::Relaxed);
+        self.nodes_visited.store(0, Ordering::Relaxed);
+        self.prims_tested.store(0, Ordering::Relaxed);
+    }
+
+    fn record(visited: u64, tested: u64) {
+        let [r, v, t] = LOCAL.get();
+        LOCAL.set([r + 1, v + visited, t + tested]);
+    }
+
+    // Suma a los totales lo contado en este hilo
+    pub fn flush(&self) {
+        let [r, v, t] = LOCAL.replace([0; 3]);
+        if r == 0 { return; }
+        self.rays.fetch_add(r, Ordering::Relaxed);
+        self.nodes_visited.fetch_add(v, Ordering::Relaxed);
+        self.prims_tested.fetch_add(t, Ordering::Relaxed);
+    }
+
+    pub fn report(&self, n_objects: usize) -> String {
+        let rays = self.rays.load(Ordering::Relaxed);
+        let nodes = self.nodes_visited.load(Ordering::Relaxed);
+        let prims = self.prims_tested.load(Ordering::Relaxed);
+        let per = |x: u64| if rays > 0 { x as f64 / rays as f64 } else { 0.0 };
+        format!("BVH: {} rayos | {:.1} nodos/rayo | {:.1} primitivas/rayo (lineal: {})",
+                rays, per(nodes), per(prims), n_objects)
+    }
+}
+
+pub struct Bvh {
+    nodes: Vec<Node>,
+    indices: Vec<u32>,   // objetos acotados, agrupados por hoja
+    unbounded: Vec<u32>, // objetos sin bounds
+    pub stats: BvhStats,
+    track: bool,         // solo el BVH de la escena cuenta; los de las mallas no
+}
+
+impl Bvh {
+    pub fn build<T: Hittable>(objects: &[T]) -> Self {
+        let mut indices = Vec::new();
+        let mut unbounded = Vec::new();
+        let mut boxes = vec![Aabb::EMPTY; objects.len()];
+        for (i, o) in objects.iter().enumerate() {
+            match o.bounds() {
+                Some(b) => { boxes[i] = b; indices.push(i as u32); }
+                None => unbounded.push(i as u32),
+            }
+        }
+        let centroids: Vec<Vec3> = boxes.iter().map(|b| b.centroid()).collect();
+
+        let mut nodes = Vec::with_capacity(2 * indices.len());
+        if !indices.is_empty() {
+            nodes.push(Node { bounds: Aabb::EMPTY, first: 0, count: 0 });
+            let n = indices.len();
+            subdivide(&mut nodes, 0, &mut indices, 0, n, &boxes, &centroids, 0);
+        }
+        Self { nodes, indices, unbounded, stats: BvhStats::default(), track: false }
+    }
+
+    // Activa el conteo de rayos/nodos/primitivas (ver `BvhStats::flush`)
+    pub fn with_stats(mut self) -> Self { self.track = true; self }
+
+    pub fn node_count(&self) -> usize { self.nodes.len() }
+
+    // Intersección más cercana
+    pub fn hit<T: Hittable>(&self, objects: &[T], r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+        let mut closest: Option<Hit> = None;
+        let mut best = 0u32;
+        let mut t_max = tmax;
+        let (mut visited, mut tested) = (0u64, 0u64);
+
+        // En empates exactos gana el índice mayor, igual que el recorrido lineal
+        // (caras coplanares de cubos que se solapan no cambian según el orden del árbol)
+        let mut test = |i: u32, t_max: &mut f32, closest: &mut Option<Hit>| {
+            tested += 1;
+            if let Some(h) = objects[i as usize].hit(r, tmin, *t_max)
+                && (h.t < *t_max || closest.is_none() || i > best) {
+                *t_max = h.t; *closest = Some(h); best = i;
+            }
+        };
+
+        for &i in &self.unbounded { test(i, &mut t_max, &mut closest); }
+
+        if let Some(root) = self.nodes.first() {
+            let inv = Vec3::new(1.0 / r.dir.x, 1.0 / r.dir.y, 1.0 / r.dir.z);
+            let mut stack = [(0u32, 0f32); STACK];
+            let mut sp = 0;
+            if let Some(t) = root.bounds.hit(r.origin, inv, tmin, t_max) { stack[0] = (0, t); sp = 1; }
+
+            while sp > 0 {
+                sp -= 1;
+                let (ni, t_enter) = stack[sp];
+                if t_enter > t_max { continue; } // ya hay algo más cerca
+                let node = &self.nodes[ni as usize];
+                visited += 1;
+
+                if node.count > 0 {
+                    for &i in &self.indices[node.first as usize..(node.first + node.count) as usize] {
+                        test(i, &mut t_max, &mut closest);
+                    }
+                    continue;
+                }
+
+                // Apilar primero el hijo lejano para visitar antes el cercano
+                let (a, b) = (node.first, node.first + 1);
+                let ta = self.nodes[a as usize].bounds.hit(r.origin, inv, tmin, t_max);
+                let tb = self.nodes[b as usize].bounds.hit(r.origin, inv, tmin, t_max);
+                match (ta, tb) {
+                    (Some(ta), Some(tb)) => {
+                        let (near, far) = if ta <= tb { ((a, ta), (b, tb)) } else { ((b, tb), (a, ta)) };
+                        stack[sp] = far; stack[sp + 1] = near; sp += 2;
+                    }
+                    (Some(ta), None) => { stack[sp] = (a, ta); sp += 1; }
+                    (None, Some(tb)) => { stack[sp] = (b, tb); sp += 1; }
+                    (None, None) => {}
+                }
+            }
+        }
+
+        if self.track { BvhStats::record(visited, tested); }
+        closest
+    }
+
+    // Cualquier intersección en (tmin, tmax) que `blocks` acepte: para rayos de sombra
+    pub fn occluded<T: Hittable>(&self, objects: &[T], r: &Ray, tmin: f32, tmax: f32, mut blocks: impl FnMut(&Hit) -> bool) -> bool {
+        let (mut visited, mut tested) = (0u64, 0u64);
+        let mut test = |i: u32| { tested += 1; objects[i as usize].hit(r, tmin, tmax).is_some_and(|h| blocks(&h)) };
+        let mut found = self.unbounded.iter().any(|&i| test(i));
+
+        if !found && !self.nodes.is_empty() {
+            let inv = Vec3::new(1.0 / r.dir.x, 1.0 / r.dir.y, 1.0 / r.dir.z);
+            let mut stack = [0u32; STACK];
+            let mut sp = 1;
+            'walk: while sp > 0 {
+                sp -= 1;
+                let node = &self.nodes[stack[sp] as usize];
+                visited += 1;
+                if node.bounds.hit(r.origin, inv, tmin, tmax).is_none() { continue; }
+                if node.count > 0 {
+                    for &i in &self.indices[node.first as usize..(node.first + node.count) as usize] {
+                        if test(i) { found = true; break 'walk; }
+                    }
+                } else {
+                    stack[sp] = node.first; stack[sp + 1] = node.first + 1; sp += 2;
+                }
+            }
+        }
+
+        if self.track { BvhStats::record(visited, tested); }
+        found
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn subdivide(nodes: &mut Vec<Node>, ni: usize, idx: &mut [u32], start: usize, end: usize,
+             boxes: &[Aabb], centroids: &[Vec3], depth: usize) {
+    let prims = &idx[start..end];
+    let bounds = prims.iter().fold(Aabb::EMPTY, |b, &i| b.union(boxes[i as usize]));
+    let cbounds = prims.iter().fold(Aabb::EMPTY, |b, &i| b.grow(centroids[i as usize]));
+    let count = end - start;
+    let leaf = Node { bounds, first: start as u32, count: count as u32 };
+
+    if count <= MAX_LEAF || depth >= MAX_DEPTH { nodes[ni] = leaf; return; }
+
+    // SAH por bins sobre los centroides, en los 3 ejes
+    let mut best: Option<(usize, usize, f32)> = None; // (eje, bin de corte, costo)
+    for axis in 0..3 {
+        let lo = cbounds.min.axis(axis);
+        let extent = cbounds.max.axis(axis) - lo;
+        if extent <= 1e-6 { continue; }
+        let scale = BINS as f32 / extent;
+
+        let mut bin_box = [Aabb::EMPTY; BINS];
+        let mut bin_cnt = [0usize; BINS];
+        for &i in prims {
+            let b = (((centroids[i as usize].axis(axis) - lo) * scale) as usize).min(BINS - 1);
+            bin_cnt[b] += 1;
+            bin_box[b] = bin_box[b].union(boxes[i as usize]);
+        }
+
+        // Barrido izquierda->derecha y derecha->izquierda
+        let mut left_area = [0f32; BINS - 1];
+        let mut left_cnt = [0usize; BINS - 1];
+        let (mut acc_b, mut acc_c) = (Aabb::EMPTY, 0);
+        for k in 0..BINS - 1 {
+            acc_b = acc_b.union(bin_box[k]); acc_c += bin_cnt[k];
+            left_area[k] = acc_b.surface_area(); left_cnt[k] = acc_c;
+        }
+        let (mut acc_b, mut acc_c) = (Aabb::EMPTY, 0);
+        for k in (1..BINS).rev() {
+            acc_b = acc_b.union(bin_box[k]); acc_c += bin_cnt[k];
+            let (lc, rc) = (left_cnt[k - 1], acc_c);
+            if lc == 0 || rc == 0 { continue; }
+            let cost = lc as f32 * left_area[k - 1] + rc as f32 * acc_b.surface_area();
+            if best.is_none_or(|(_, _, c)| cost < c) { best = Some((axis, k, cost)); }
+        }
+    }
+
+    let Some((axis, split, cost)) = best else { nodes[ni] = leaf; return; }; // centroides coincidentes
+    if cost >= count as f32 * bounds.surface_area() && count <= HARD_LEAF { nodes[ni] = leaf; return; }
+
+    // Partición in-place según el bin de corte
+    let lo = cbounds.min.axis(axis);
+    let scale = BINS as f32 / (cbounds.max.axis(axis) - lo);
+    let slice = &mut idx[start..end];
+    let mut mid = 0;
+    for j in 0..slice.len() {
+        let b = (((centroids[slice[j] as usize].axis(axis) - lo) * scale) as usize).min(BINS - 1);
+        if b < split { slice.swap(j, mid); mid += 1; }
+    }
+    let mid = start + mid;
+
+    let left = nodes.len();
+    nodes.push(leaf); nodes.push(leaf);
+    nodes[ni] = Node { bounds, first: left as u32, count: 0 };
+    subdivide(nodes, left, idx, start, mid, boxes, centroids, depth + 1);
+    subdivide(nodes, left + 1, idx, mid, end, boxes, centroids, depth + 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampling::Rng;
+    use crate::scene::Scene;
+
+    // Recorrido lineal de referencia: en empates gana el índice mayor
+    fn linear<T: Hittable>(objects: &[T], r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+        let mut closest: Option<Hit> = None;
+        for o in objects {
+            if let Some(h) = o.hit(r, tmin, closest.as_ref().map_or(tmax, |c| c.t))
+                && closest.as_ref().is_none_or(|c| h.t <= c.t) { closest = Some(h); }
+        }
+        closest
+    }
+
+    #[test]
+    fn matches_linear_scan_on_diorama() {
+        let scene = Scene::diorama(0.4);
+        let mut rng = Rng::new(7);
+        let mut f = |a: f32, b: f32| a + (b - a) * rng.next_f32();
+        for _ in 0..4000 {
+            let origin = Vec3::new(f(-6.0, 6.0), f(-1.0, 5.0), f(-6.0, 6.0));
+            let dir = Vec3::new(f(-1.0, 1.0), f(-1.0, 1.0), f(-1.0, 1.0)).normalize();
+            let r = Ray::new(origin, dir);
+            let tmax = f(0.5, 20.0);
+            let a = scene.bvh.hit(&scene.objects, &r, 1e-3, tmax);
+            let b = linear(&scene.objects, &r, 1e-3, tmax);
+            assert_eq!(a.as_ref().map(|h| (h.t, h.material_id)), b.as_ref().map(|h| (h.t, h.material_id)));
+            assert_eq!(scene.bvh.occluded(&scene.objects, &r, 1e-3, tmax, |_| true), b.is_some());
+        }
+    }
+}
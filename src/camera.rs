@@ -1,26 +1,183 @@
 use crate::vec3::Vec3;
 use crate::ray::Ray;
+use crate::sampling::{concentric_disk, regular_polygon};
 
+// Forma de la apertura: define cómo se ven las luces desenfocadas (bokeh)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Bokeh { Disk, Polygon(u32) }
+
+impl std::str::FromStr for Bokeh {
+    type Err = String;
+    // "disk" o el número de láminas del diafragma (>= 3)
+    fn from_str(s: &str) -> Result<Self, String> {
+        if s == "disk" { return Ok(Bokeh::Disk); }
+        match s.parse::<u32>() {
+            Ok(n) if n >= 3 => Ok(Bokeh::Polygon(n)),
+            _ => Err(format!("bokeh inválido '{}'", s)),
+        }
+    }
+}
+
+// Proyección: las paralelas encuadran `view_height` unidades en vertical; las panorámicas salen de `pos`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Projection { Perspective, Orthographic, Isometric, Dimetric, Equirectangular, Fisheye }
+
+impl Projection {
+    // Siguiente proyección (tecla del viewer)
+    pub fn next(self) -> Self {
+        match self {
+            Projection::Perspective => Projection::Orthographic, Projection::Orthographic => Projection::Isometric,
+            Projection::Isometric => Projection::Dimetric, Projection::Dimetric => Projection::Equirectangular,
+            Projection::Equirectangular => Projection::Fisheye, Projection::Fisheye => Projection::Perspective,
+        }
+    }
+    pub fn name(self) -> &'static str {
+        match self {
+            Projection::Perspective => "perspective", Projection::Orthographic => "ortho", Projection::Isometric => "iso", Projection::Dimetric => "dimetric",
+            Projection::Equirectangular => "equirect", Projection::Fisheye => "fisheye",
+        }
+    }
+}
+
+impl std::str::FromStr for Projection {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "perspective" => Ok(Projection::Perspective),
+            "ortho" => Ok(Projection::Orthographic),
+            "iso" => Ok(Projection::Isometric),
+            "dimetric" => Ok(Projection::Dimetric),
+            "equirect" => Ok(Projection::Equirectangular),
+            "fisheye" => Ok(Projection::Fisheye),
+            _ => Err(format!("proyección desconocida '{}'", s)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
 pub struct Camera {
     pub pos: Vec3,
     pub target: Vec3,
     pub up: Vec3,
     pub fov_deg: f32,
     pub aspect: f32,
+    pub aperture: f32,   // radio de la lente; 0 = pinhole (todo enfocado)
+    pub focus_dist: f32, // distancia (sobre el eje de la vista) al plano enfocado
+    pub bokeh: Bokeh,
+    pub projection: Projection,
+    pub view_height: f32, // alto visible en las proyecciones paralelas
+    pub fisheye_deg: f32, // ángulo que cubre el círculo del ojo de pez (360 = esfera completa)
+    pub iod: f32,         // estéreo: distancia entre los ojos
+    pub convergence: f32, // estéreo: distancia del plano sin paralaje (la pantalla)
+    pub eye: f32,         // -1 = ojo izquierdo, 1 = derecho, 0 = centro (mono)
 }
 
 impl Camera {
-    pub fn ray_for_pixel(&self, x: u32, y: u32, w: u32, h: u32) -> Ray {
-        // Base on look-at vectors
-        let wv = (self.pos - self.target).normalize();
+    // Cámara en `pos` mirando a `target`. Empieza como pinhole en perspectiva, enfocada en
+    // el target; `view_height` encuadra en paralela lo mismo que la perspectiva a esa distancia.
+    pub fn look_at(pos: Vec3, target: Vec3, fov_deg: f32, aspect: f32) -> Self {
+        let focus_dist = (target - pos).length();
+        let view_height = 2.0 * focus_dist * (fov_deg.to_radians() * 0.5).tan();
+        Self { pos, target, up: Vec3::new(0.0,1.0,0.0), fov_deg, aspect, aperture: 0.0, focus_dist, bokeh: Bokeh::Disk,
+               projection: Projection::Perspective, view_height, fisheye_deg: 180.0,
+               iod: focus_dist / 30.0, convergence: focus_dist, eye: 0.0 }
+    }
+
+    // Base de la vista: (hacia atrás, derecha, arriba)
+    fn basis(&self) -> (Vec3, Vec3, Vec3) {
+        let back = self.pos - self.target;
+        let wv = match self.projection {
+            Projection::Perspective | Projection::Orthographic | Projection::Fisheye => back.normalize(),
+            // panorama: mirando en horizontal, con el horizonte en el centro de la imagen
+            Projection::Equirectangular => Vec3::new(back.x, 0.0, back.z).normalize(),
+            Projection::Isometric | Projection::Dimetric => {
+                use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+                let elev = if self.projection == Projection::Isometric { (0.5f32).sqrt().atan() } else { (0.5f32).atan() };
+                let az = back.x.atan2(back.z);
+                let az = ((az - FRAC_PI_4) / FRAC_PI_2).round() * FRAC_PI_2 + FRAC_PI_4;
+                Vec3::new(az.sin() * elev.cos(), elev.sin(), az.cos() * elev.cos())
+            }
+        };
         let u = self.up.cross(wv).normalize();
-        let v = wv.cross(u);
-        let fov = (self.fov_deg.to_radians() * 0.5).tan();
+        (wv, u, wv.cross(u))
+    }
+
+    // Cámara de un ojo (`side` = -1 izquierdo, 1 derecho). Proyección descentrada (off-axis):
+    // el ojo se corre iod/2 a un costado y el frustum se cizalla para que ambos compartan el
+    // plano de convergencia; los ejes quedan paralelos, así no hay paralaje vertical como al
+    // girar los ojos hacia adentro (toe-in). Solo afecta a la perspectiva.
+    pub fn for_eye(&self, side: f32) -> Self { Self { eye: side, ..*self } }
+
+    // Rayo por el centro del píxel (x, y) y el centro de la lente
+    pub fn ray_for_pixel(&self, x: u32, y: u32, w: u32, h: u32) -> Option<Ray> {
+        self.ray_through(x as f32 + 0.5, y as f32 + 0.5, w, h, (0.5, 0.5))
+    }
+
+    // Rayo por (px, py) en píxeles; `lens` en [0,1)² elige el punto de la apertura (solo en perspectiva).
+    // None fuera del círculo del ojo de pez
+    pub fn ray_through(&self, px: f32, py: f32, w: u32, h: u32, lens: (f32, f32)) -> Option<Ray> {
+        use std::f32::consts::PI;
+        let (wv, u, v) = self.basis();
+        let sx = 2.0 * px / w as f32 - 1.0;
+        let sy = 1.0 - 2.0 * py / h as f32;
+
+        match self.projection {
+            Projection::Perspective => {}
+            Projection::Orthographic | Projection::Isometric | Projection::Dimetric => {
+                let half = self.view_height * 0.5;
+                let dist = (self.pos - self.target).length();
+                let origin = self.target + wv * dist + u * (sx * self.aspect * half) + v * (sy * half);
+                return Some(Ray::new(origin, -wv));
+            }
+            Projection::Equirectangular => {
+                // x: longitud en [-π, π) (los bordes se unen detrás de la cámara); y: latitud
+                let (lon, lat) = (sx * PI, sy * PI * 0.5);
+                let dir = (wv * -lon.cos() + u * lon.sin()) * lat.cos() + v * lat.sin();
+                return Some(Ray::new(self.pos, dir));
+            }
+            Projection::Fisheye => {
+                // equidistante: el ángulo con el eje crece lineal con la distancia al centro
+                let (dx, dy) = (sx * self.aspect, sy);
+                let r = (dx * dx + dy * dy).sqrt();
+                if r > 1.0 { return None; }
+                let theta = r * self.fisheye_deg.to_radians() * 0.5;
+                let (c, s) = if r > 0.0 { (dx / r, dy / r) } else { (1.0, 0.0) };
+                let dir = wv * -theta.cos() + (u * c + v * s) * theta.sin();
+                return Some(Ray::new(self.pos, dir));
+            }
+        }
 
         // NDC -> dirección
-        let ndc_x = (2.0 * (x as f32 + 0.5) / w as f32 - 1.0) * self.aspect * fov;
-        let ndc_y = (1.0 - 2.0 * (y as f32 + 0.5) / h as f32) * fov;
-        let dir = (u * ndc_x + v * ndc_y + (wv * -1.0)).normalize();
-        Ray::new(self.pos, dir)
+        let fov = (self.fov_deg.to_radians() * 0.5).tan();
+        let shift = self.eye * self.iod * 0.5;
+        let eye = self.pos + u * shift;
+        let dir = u * (sx * self.aspect * fov - shift / self.convergence) + v * (sy * fov) + (wv * -1.0); // componente 1 sobre el eje de la vista
+        if self.aperture <= 0.0 { return Some(Ray::new(eye, dir)); }
+
+        let (lx, ly) = match self.bokeh {
+            Bokeh::Disk => concentric_disk(lens.0, lens.1),
+            Bokeh::Polygon(n) => regular_polygon(n, lens.0, lens.1),
+        };
+        let origin = eye + (u * lx + v * ly) * self.aperture;
+        let focus = eye + dir * self.focus_dist;
+        Some(Ray::new(origin, focus - origin))
+    }
+
+    // Autofoco: mediana de la profundidad en una ventana de 5×5 píxeles en el centro
+    // de `depth` (distancias del último frame). None si ahí solo se ve cielo.
+    pub fn focus_from_depth(depth: &[f32], width: u32, height: u32) -> Option<f32> {
+        const SKY: f32 = 1000.0;
+        let (cx, cy) = (width as i32 / 2, height as i32 / 2);
+        let mut ds: Vec<f32> = Vec::with_capacity(25);
+        for y in cy - 2..=cy + 2 {
+            for x in cx - 2..=cx + 2 {
+                if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 { continue; }
+                let d = depth[(y as u32 * width + x as u32) as usize];
+                if d.is_finite() && d < SKY { ds.push(d); }
+            }
+        }
+        if ds.is_empty() { return None; }
+        ds.sort_by(|a, b| a.total_cmp(b));
+        Some(ds[ds.len() / 2])
     }
 }
@@ -0,0 +1,193 @@
+// Opciones de línea de comandos (viewer interactivo y render offline)
+
+use diorama::render::{RenderSettings, Shading, Integrator, Stereo};
+use diorama::{Filter, Bokeh, Projection};
+
+pub struct Options {
+    pub width: u32,
+    pub height: u32,
+    pub yaw_deg: f32,
+    pub pitch_deg: f32,
+    pub cam_dist: f32,
+    pub fov: f32,
+    pub is_night: bool,
+    pub threads: usize,               // 0 = todos los núcleos
+    pub light_samples: u32,           // rayos de sombra por luz de área
+    pub phong: bool,                  // modelo Phong original en vez de GGX
+    pub gloss_samples: u32,           // muestras de reflejo/refracción rugosa
+    pub path_trace: bool,             // path tracer con iluminación global en vez de Whitted
+    pub spp: u32,                     // muestras por píxel (por frame en el viewer)
+    pub filter: Filter,               // filtro de reconstrucción del anti-aliasing
+    pub projection: Projection,
+    pub view_height: Option<f32>,     // alto visible en proyección paralela; None = según --dist y --fov
+    pub fisheye_deg: f32,             // ángulo del círculo del ojo de pez
+    pub stereo: Stereo,
+    pub iod: Option<f32>,             // distancia entre ojos; None = 1/30 de la distancia al centro
+    pub convergence: Option<f32>,     // plano sin paralaje; None = el centro del diorama
+    pub aperture: f32,                // radio de la lente (0 = pinhole)
+    pub focus: Option<f32>,           // distancia de foco; None = autofoco al centro
+    pub bokeh: Bokeh,
+    pub ao_samples: u32,              // rayos de oclusión ambiental (0 = ambiente plano)
+    pub ao_radius: f32,
+    pub output: Option<String>,       // Some(..) => render headless a PNG
+    pub depth_output: Option<String>, // PNG opcional con el depth buffer
+    pub ao_output: Option<String>,    // PNG opcional con la pasada de oclusión ambiental
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        // W/H por variables de entorno siguen funcionando como default
+        let width = std::env::var("W").ok().and_then(|s| s.parse().ok()).unwrap_or(800);
+        let height = std::env::var("H").ok().and_then(|s| s.parse().ok()).unwrap_or(450);
+        Self {
+            width, height,
+            yaw_deg: 35.0,
+            pitch_deg: 13.0,
+            cam_dist: 8.5,
+            fov: 60.0,
+            is_night: true, // Empezar en modo nocturno para ver la luna
+            threads: 0,
+            light_samples: 4,
+            phong: false,
+            gloss_samples: 4,
+            path_trace: false,
+            spp: 1,
+            filter: Filter::Box,
+            projection: Projection::Perspective,
+            view_height: None,
+            fisheye_deg: 180.0,
+            stereo: Stereo::Mono,
+            iod: None,
+            convergence: None,
+            aperture: 0.0,
+            focus: None,
+            bokeh: Bokeh::Disk,
+            ao_samples: 4,
+            ao_radius: 0.6,
+            output: None,
+            depth_output: None,
+            ao_output: None,
+        }
+    }
+}
+
+const USAGE: &str = "\
+uso: Proyecto2_Diorama [opciones]
+
+  --out <archivo.png>     render offline (sin ventana) y guarda el PNG
+  --depth <archivo.png>   (con --out) guarda también el depth buffer
+  --ao <archivo.png>      (con --out) guarda también la pasada de oclusión ambiental
+  --width <px>            ancho (default: $W o 800)
+  --height <px>           alto  (default: $H o 450)
+  --yaw <grados>          órbita de la cámara alrededor del diorama (default: 35)
+  --pitch <grados>        altura de la órbita: ángulo sobre el horizonte (default: 13)
+  --dist <unidades>       distancia de la cámara al centro del diorama (default: 8.5)
+  --fov <grados>          campo de visión vertical (default: 60)
+  --day | --night         modo de iluminación (default: noche)
+  --threads <n>           hilos de render (default: 0 = todos los núcleos)
+  --light-samples <n>     rayos de sombra por luz de área (default: 4)
+  --phong                 sombreado Phong original (default: GGX metálico/rugosidad)
+  --gloss-samples <n>     rayos por reflejo/refracción rugosa (default: 4)
+  --path-trace            path tracing con iluminación global (default: Whitted)
+  --spp <n>               muestras por píxel; en el viewer, por frame (default: 1)
+  --filter <nombre>       filtro del anti-aliasing: box, tent, gaussian, mitchell (default: box)
+  --projection <tipo>     perspective, ortho, iso, dimetric, equirect (360°, usar imagen 2:1)
+                          o fisheye (default: perspective)
+  --view-height <unid.>   alto visible en ortho/iso/dimetric (default: el que ve la perspectiva en el centro)
+  --fisheye-fov <grados>  ángulo que cubre el ojo de pez, hasta 360 (default: 180)
+  --stereo <modo>         mono, sbs (lado a lado), ou (arriba/abajo) o anaglyph (rojo/cian) (default: mono)
+  --iod <dist>            distancia entre los ojos (default: 1/30 de la distancia al centro)
+  --convergence <dist>    distancia del plano sin paralaje (default: el centro del diorama)
+  --aperture <radio>      profundidad de campo: radio de la lente (default: 0 = todo enfocado)
+  --focus <dist>          distancia de foco (default: autofoco al centro de la imagen)
+  --bokeh <disk|n>        forma de la apertura: disco o polígono de n láminas (default: disk)
+  --ao-samples <n>        rayos de oclusión ambiental, 0 = ambiente plano (default: 4)
+  --ao-radius <unidades>  alcance de la oclusión ambiental (default: 0.6)
+  -h, --help              muestra esta ayuda";
+
+impl Options {
+    pub fn from_args() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(o) => o,
+            Err(msg) => {
+                eprintln!("error: {}\n\n{}", msg, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // Parámetros del render que vienen de la línea de comandos
+    pub fn render_settings(&self) -> RenderSettings {
+        RenderSettings {
+            threads: self.threads,
+            light_samples: self.light_samples,
+            shading: if self.phong { Shading::Phong } else { Shading::Pbr },
+            gloss_samples: self.gloss_samples,
+            samples: self.spp,
+            filter: self.filter,
+            stereo: self.stereo,
+            ao_samples: self.ao_samples,
+            ao_radius: self.ao_radius,
+            integrator: if self.path_trace { Integrator::PathTracer } else { Integrator::Whitted },
+            ..Default::default()
+        }
+    }
+
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        fn value<T: std::str::FromStr>(flag: &str, v: Option<String>) -> Result<T, String> {
+            let v = v.ok_or_else(|| format!("falta el valor de {}", flag))?;
+            v.parse().map_err(|_| format!("valor inválido para {}: '{}'", flag, v))
+        }
+
+        let mut o = Self::default();
+        while let Some(a) = args.next() {
+            match a.as_str() {
+                "--out" | "-o" => o.output = Some(value(&a, args.next())?),
+                "--depth"      => o.depth_output = Some(value(&a, args.next())?),
+                "--ao"         => o.ao_output = Some(value(&a, args.next())?),
+                "--width"      => o.width = value(&a, args.next())?,
+                "--height"     => o.height = value(&a, args.next())?,
+                "--yaw"        => o.yaw_deg = value(&a, args.next())?,
+                "--pitch"      => o.pitch_deg = value(&a, args.next())?,
+                "--dist"       => o.cam_dist = value(&a, args.next())?,
+                "--fov"        => o.fov = value(&a, args.next())?,
+                "--day"        => o.is_night = false,
+                "--night"      => o.is_night = true,
+                "--threads"    => o.threads = value(&a, args.next())?,
+                "--light-samples" => o.light_samples = value(&a, args.next())?,
+                "--phong"      => o.phong = true,
+                "--gloss-samples" => o.gloss_samples = value(&a, args.next())?,
+                "--path-trace" => o.path_trace = true,
+                "--spp"        => o.spp = value(&a, args.next())?,
+                "--filter"     => o.filter = value(&a, args.next())?,
+                "--projection" => o.projection = value(&a, args.next())?,
+                "--view-height" => o.view_height = Some(value(&a, args.next())?),
+                "--fisheye-fov" => o.fisheye_deg = value(&a, args.next())?,
+                "--stereo"     => o.stereo = value(&a, args.next())?,
+                "--iod"        => o.iod = Some(value(&a, args.next())?),
+                "--convergence" => o.convergence = Some(value(&a, args.next())?),
+                "--aperture"   => o.aperture = value(&a, args.next())?,
+                "--focus"      => o.focus = Some(value(&a, args.next())?),
+                "--bokeh"      => o.bokeh = value(&a, args.next())?,
+                "--ao-samples" => o.ao_samples = value(&a, args.next())?,
+                "--ao-radius"  => o.ao_radius = value(&a, args.next())?,
+                "-h" | "--help" => { println!("{}", USAGE); std::process::exit(0); }
+                _ => return Err(format!("opción desconocida '{}'", a)),
+            }
+        }
+        if o.width == 0 || o.height == 0 { return Err("la resolución debe ser mayor que 0".into()); }
+        if o.light_samples == 0 || o.gloss_samples == 0 { return Err("--light-samples y --gloss-samples deben ser mayores que 0".into()); }
+        if o.spp == 0 { return Err("--spp debe ser mayor que 0".into()); }
+        if o.cam_dist <= 0.0 { return Err("--dist debe ser mayor que 0".into()); }
+        if o.view_height.is_some_and(|h| h <= 0.0) { return Err("--view-height debe ser mayor que 0".into()); }
+        if !(1.0..=360.0).contains(&o.fisheye_deg) { return Err("--fisheye-fov debe estar entre 1 y 360".into()); }
+        if o.iod.is_some_and(|d| d < 0.0) { return Err("--iod no puede ser negativo".into()); }
+        if o.convergence.is_some_and(|d| d <= 0.0) { return Err("--convergence debe ser mayor que 0".into()); }
+        if o.aperture < 0.0 { return Err("--aperture no puede ser negativo".into()); }
+        if o.focus.is_some_and(|f| f <= 0.0) { return Err("--focus debe ser mayor que 0".into()); }
+        if o.ao_radius <= 0.0 { return Err("--ao-radius debe ser mayor que 0".into()); }
+        if o.depth_output.is_some() && o.output.is_none() { return Err("--depth requiere --out".into()); }
+        if o.ao_output.is_some() && o.output.is_none() { return Err("--ao requiere --out".into()); }
+        Ok(o)
+    }
+}
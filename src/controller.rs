@@ -0,0 +1,93 @@
+use crate::vec3::Vec3;
+use crate::camera::Camera;
+
+// Controladores de cámara del viewer, sin depender de raylib: el viewer traduce
+// teclado y mouse a un `ControlInput` por frame.
+// - Órbita: gira (yaw/pitch) alrededor de un target, se desplaza (pan) y acerca (zoom).
+// - Vuelo libre (FPS): WASD + mirar con el mouse; el target queda `dist` adelante.
+// Ambos comparten yaw/pitch de la dirección de la vista, así cambiar de modo no mueve la cámara.
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CameraMode { Orbit, FreeFly }
+
+// Entrada de un frame
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ControlInput {
+    pub look: (f32, f32), // arrastre para girar/mirar (píxeles; x a la derecha, y hacia abajo)
+    pub pan: (f32, f32),  // arrastre para desplazar (píxeles, solo órbita)
+    pub zoom: f32,        // rueda: positivo acerca (órbita) o avanza (vuelo)
+    pub walk: Vec3,       // teclado en [-1, 1]: x derecha, y arriba, z adelante
+    pub dt: f32,          // segundos desde el frame anterior
+    pub fast: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CameraController {
+    pub mode: CameraMode,
+    pub pos: Vec3,
+    pub target: Vec3,
+    pub yaw: f32,   // radianes; 0 = mirando hacia -Z
+    pub pitch: f32, // radianes; positivo = mirando hacia arriba
+    pub dist: f32,  // distancia pos -> target
+}
+
+impl CameraController {
+    const LOOK_SPEED: f32 = 0.005;  // radianes por píxel
+    const PAN_SPEED: f32 = 0.0015;  // fracción de `dist` por píxel
+    const WALK_SPEED: f32 = 2.0;    // unidades por segundo
+    const ORBIT_KEYS: f32 = 1.2;    // radianes por segundo (A/D en órbita)
+    const MAX_PITCH: f32 = 1.55;    // casi vertical, sin dar la vuelta
+
+    // Órbita alrededor de `target`; `pitch` positivo mira desde arriba
+    pub fn orbit(target: Vec3, yaw: f32, pitch: f32, dist: f32) -> Self {
+        let mut c = Self { mode: CameraMode::Orbit, pos: target, target, yaw, pitch: -pitch, dist };
+        c.sync();
+        c
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        let (sy, cy) = self.yaw.sin_cos();
+        let (sp, cp) = self.pitch.sin_cos();
+        Vec3::new(sy * cp, sp, -cy * cp)
+    }
+
+    // Órbita <-> vuelo libre, desde el mismo punto de vista
+    pub fn toggle_mode(&mut self) {
+        self.mode = if self.mode == CameraMode::Orbit { CameraMode::FreeFly } else { CameraMode::Orbit };
+    }
+
+    pub fn update(&mut self, input: &ControlInput) {
+        // arrastrar hacia abajo mira más hacia abajo (en órbita: sube la cámara)
+        self.yaw += input.look.0 * Self::LOOK_SPEED;
+        self.pitch = (self.pitch - input.look.1 * Self::LOOK_SPEED).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+        if self.mode == CameraMode::Orbit { self.yaw += input.walk.x * Self::ORBIT_KEYS * input.dt; }
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let right = self.forward().cross(up).normalize();
+        match self.mode {
+            CameraMode::Orbit => {
+                let cam_up = right.cross(self.forward());
+                let k = self.dist * Self::PAN_SPEED;
+                self.target = self.target + right * (-input.pan.0 * k) + cam_up * (input.pan.1 * k);
+                let zoom = input.zoom * 0.1 + input.walk.z * input.dt;
+                self.dist = (self.dist * (1.0 - zoom)).clamp(0.5, 50.0);
+            }
+            CameraMode::FreeFly => {
+                let speed = Self::WALK_SPEED * if input.fast { 3.0 } else { 1.0 };
+                let fwd = self.forward();
+                let step = right * input.walk.x + up * input.walk.y + fwd * input.walk.z;
+                self.pos = self.pos + step * (speed * input.dt) + fwd * (input.zoom * 0.2);
+            }
+        }
+        self.sync();
+    }
+
+    // Recalcula el punto derivado: la posición en órbita, el target en vuelo
+    fn sync(&mut self) {
+        match self.mode {
+            CameraMode::Orbit => self.pos = self.target - self.forward() * self.dist,
+            CameraMode::FreeFly => self.target = self.pos + self.forward() * self.dist,
+        }
+    }
+
+    pub fn camera(&self, fov_deg: f32, aspect: f32) -> Camera { Camera::look_at(self.pos, self.target, fov_deg, aspect) }
+}
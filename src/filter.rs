@@ -0,0 +1,93 @@
+// Filtros de reconstrucción del anti-aliasing. Las muestras de cada píxel se distribuyen
+// según |f| alrededor del centro (en píxeles) y pesan con el signo de f, así una muestra
+// en un lóbulo negativo no anula el píxel. Todos son separables: f(x, y) = f(x)·f(y).
+
+const TABLE: usize = 64; // celdas por eje de la tabla de muestreo
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Filter { Box, Tent, Gaussian, Mitchell }
+
+impl Filter {
+    // Semiancho del soporte: las muestras caen en [-radius, radius]² alrededor del centro
+    pub fn radius(self) -> f32 {
+        match self { Filter::Box => 0.5, Filter::Tent => 1.0, Filter::Gaussian => 1.5, Filter::Mitchell => 2.0 }
+    }
+
+    pub fn eval(self, dx: f32, dy: f32) -> f32 { self.eval_1d(dx) * self.eval_1d(dy) }
+
+    fn eval_1d(self, x: f32) -> f32 {
+        let x = x.abs();
+        let r = self.radius();
+        if x > r { return 0.0; }
+        match self {
+            Filter::Box => 1.0,
+            Filter::Tent => 1.0 - x / r,
+            // Gaussiana (α = 2) desplazada para llegar a 0 en el borde
+            Filter::Gaussian => (-2.0 * x * x).exp() - (-2.0 * r * r).exp(),
+            // Mitchell–Netravali con B = C = 1/3 (lóbulos negativos: bordes más nítidos)
+            Filter::Mitchell => {
+                let (b, c) = (1.0 / 3.0, 1.0 / 3.0);
+                let k = if x < 1.0 {
+                    (12.0 - 9.0*b - 6.0*c) * x*x*x + (-18.0 + 12.0*b + 6.0*c) * x*x + (6.0 - 2.0*b)
+                } else {
+                    (-b - 6.0*c) * x*x*x + (6.0*b + 30.0*c) * x*x + (-12.0*b - 48.0*c) * x + (8.0*b + 24.0*c)
+                };
+                k / 6.0
+            }
+        }
+    }
+
+    // Tabla para muestrear |f| por eje; se arma una vez por tile
+    pub fn sampler(self) -> FilterSampler {
+        let r = self.radius();
+        let (mut cdf, mut sign) = ([0.0; TABLE + 1], [1.0; TABLE]);
+        for i in 0..TABLE {
+            let f = self.eval_1d(-r + (i as f32 + 0.5) * 2.0 * r / TABLE as f32);
+            sign[i] = if f < 0.0 { -1.0 } else { 1.0 };
+            cdf[i + 1] = cdf[i] + f.abs();
+        }
+        let total = cdf[TABLE];
+        for c in &mut cdf { *c /= total; }
+        FilterSampler { r, cdf, sign }
+    }
+
+    // Siguiente filtro (tecla del viewer)
+    pub fn next(self) -> Self {
+        match self { Filter::Box => Filter::Tent, Filter::Tent => Filter::Gaussian, Filter::Gaussian => Filter::Mitchell, Filter::Mitchell => Filter::Box }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self { Filter::Box => "box", Filter::Tent => "tent", Filter::Gaussian => "gaussian", Filter::Mitchell => "mitchell" }
+    }
+}
+
+// |f| constante por celdas: devuelve el desplazamiento (dx, dy) y el peso ±1
+pub struct FilterSampler { r: f32, cdf: [f32; TABLE + 1], sign: [f32; TABLE] }
+
+impl FilterSampler {
+    pub fn sample(&self, u: (f32, f32)) -> (f32, f32, f32) {
+        let (dx, sx) = self.sample_1d(u.0);
+        let (dy, sy) = self.sample_1d(u.1);
+        (dx, dy, sx * sy)
+    }
+
+    fn sample_1d(&self, u: f32) -> (f32, f32) {
+        let i = (self.cdf.partition_point(|&c| c <= u) - 1).min(TABLE - 1);
+        let width = self.cdf[i + 1] - self.cdf[i];
+        let t = if width > 0.0 { (u - self.cdf[i]) / width } else { 0.5 };
+        (-self.r + (i as f32 + t) * 2.0 * self.r / TABLE as f32, self.sign[i])
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "box" => Ok(Filter::Box),
+            "tent" => Ok(Filter::Tent),
+            "gaussian" => Ok(Filter::Gaussian),
+            "mitchell" => Ok(Filter::Mitchell),
+            _ => Err(format!("filtro desconocido '{}'", s)),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::vec3::Vec3;
 use crate::ray::Ray;
 use crate::transform::Transform;
+use crate::aabb::Aabb;
+use crate::mesh::Mesh;
 
 pub struct Hit {
     pub t: f32,
@@ -12,6 +14,8 @@ pub struct Hit {
 
 pub trait Hittable {
     fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit>;
+    // Caja en espacio mundo; None si no está acotado
+    fn bounds(&self) -> Option<Aabb>;
 }
 
 pub struct Cube {
@@ -23,7 +27,11 @@ pub struct Cube {
 impl Cube {
     pub fn new(center: Vec3, half: Vec3, rot_y_rad: f32, material_id: usize) -> Self {
         let rot = crate::transform::Mat3::rotate_y(rot_y_rad);
-        let tr = Transform::new(rot, center);
+        let tr = Transform::rigid(rot, center);
+        Self { half, tr, material_id }
+    }
+    // Orientación/escala arbitrarias (techos inclinados, pilares ladeados...)
+    pub fn with_transform(tr: Transform, half: Vec3, material_id: usize) -> Self {
         Self { half, tr, material_id }
     }
 }
@@ -73,16 +81,203 @@ impl Hittable for Cube {
         };
 
         let p_world = r.at(t_hit);
-        let n_world = self.tr.local_to_world_dir(n_local).normalize();
+        let n_world = self.tr.local_to_world_normal(n_local);
 
         Some(Hit { t: t_hit, p: p_world, n: n_world, uv: (u,v), material_id: self.material_id })
     }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let h = self.half;
+        let corners: Vec<Vec3> = (0..8).map(|i| {
+            let c = Vec3::new(if i&1==0 { -h.x } else { h.x }, if i&2==0 { -h.y } else { h.y }, if i&4==0 { -h.z } else { h.z });
+            self.tr.local_to_world_point(c)
+        }).collect();
+        Some(Aabb::from_points(&corners))
+    }
+}
+
+pub struct Sphere {
+    pub radius: f32,
+    pub tr: Transform, // orienta el mapeo UV; con escala no uniforme es un elipsoide
+    pub material_id: usize,
+}
+
+impl Sphere {
+    pub fn new(center: Vec3, radius: f32, material_id: usize) -> Self {
+        Self::with_transform(Transform::translate(center), radius, material_id)
+    }
+    pub fn with_transform(tr: Transform, radius: f32, material_id: usize) -> Self {
+        Self { radius, tr, material_id }
+    }
 }
 
-pub enum Object { Cube(Cube) }
+impl Hittable for Sphere {
+    fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+        // Esfera centrada en el origen del espacio local
+        let ro = self.tr.world_to_local_point(r.origin);
+        let rd = self.tr.world_to_local_dir(r.dir);
+
+        let a = rd.dot(rd);
+        let half_b = ro.dot(rd);
+        let c = ro.dot(ro) - self.radius*self.radius;
+        let disc = half_b*half_b - a*c;
+        if disc < 0.0 { return None; }
+        let sq = disc.sqrt();
+        let mut t_hit = (-half_b - sq) / a;
+        if t_hit < tmin || t_hit > tmax {
+            t_hit = (-half_b + sq) / a;
+            if t_hit < tmin || t_hit > tmax { return None; }
+        }
+
+        let n_local = (ro + rd * t_hit) * (1.0 / self.radius);
+        // UV esférico: u = longitud, v = latitud
+        let u = 0.5 + n_local.z.atan2(n_local.x) / (2.0 * std::f32::consts::PI);
+        let v = 0.5 + n_local.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+
+        let p_world = r.at(t_hit);
+        let n_world = self.tr.local_to_world_normal(n_local);
+
+        Some(Hit { t: t_hit, p: p_world, n: n_world, uv: (u,v), material_id: self.material_id })
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        // Elipsoide transformado: semieje en cada eje de mundo = radio * |fila i de m|
+        let m = &self.tr.m;
+        let r = Vec3::new(m.row(0).length(), m.row(1).length(), m.row(2).length()) * self.radius;
+        Some(Aabb::new(self.tr.pos - r, self.tr.pos + r))
+    }
+}
+
+// Intersección con el plano local y=0; devuelve (t, punto local)
+fn hit_local_xz(ro: Vec3, rd: Vec3, tmin: f32, tmax: f32) -> Option<(f32, Vec3)> {
+    if rd.y.abs() < 1e-8 { return None; }
+    let t = -ro.y / rd.y;
+    if t < tmin || t > tmax { return None; }
+    Some((t, ro + rd * t))
+}
+
+// Normal de superficie de dos caras: siempre mirando hacia el rayo
+fn facing(n: Vec3, dir: Vec3) -> Vec3 { if n.dot(dir) > 0.0 { n * -1.0 } else { n } }
+
+// Plano infinito: y=0 en espacio local, normal +Y. UV en unidades de mundo (tilea)
+pub struct Plane {
+    pub tr: Transform,
+    pub uv_scale: f32, // repeticiones de textura por unidad
+    pub material_id: usize,
+}
+
+impl Plane {
+    // None si la normal es nula
+    pub fn new(point: Vec3, normal: Vec3, material_id: usize) -> Option<Self> {
+        let n = normal.normalize();
+        let a = if n.y.abs() < 0.99 { Vec3::new(0.0,1.0,0.0) } else { Vec3::new(1.0,0.0,0.0) };
+        let x = a.cross(n).normalize();
+        let z = x.cross(n);
+        let rot = crate::transform::Mat3 { c0: x, c1: n, c2: z };
+        Some(Self::with_transform(Transform::new(rot, point)?, material_id))
+    }
+    pub fn with_transform(tr: Transform, material_id: usize) -> Self {
+        Self { tr, uv_scale: 1.0, material_id }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+        let ro = self.tr.world_to_local_point(r.origin);
+        let rd = self.tr.world_to_local_dir(r.dir);
+        let (t_hit, p_local) = hit_local_xz(ro, rd, tmin, tmax)?;
+
+        let uv = (p_local.x * self.uv_scale, p_local.z * self.uv_scale);
+        let n_world = facing(self.tr.local_to_world_normal(Vec3::new(0.0,1.0,0.0)), r.dir);
+        Some(Hit { t: t_hit, p: r.at(t_hit), n: n_world, uv, material_id: self.material_id })
+    }
+
+    fn bounds(&self) -> Option<Aabb> { None }
+}
+
+// Rectángulo finito en el plano local XZ (normal +Y), de tamaño 2*half_u x 2*half_v
+pub struct Quad {
+    pub half_u: f32,
+    pub half_v: f32,
+    pub tr: Transform,
+    pub uv_scale: f32, // repeticiones de textura por unidad, desde la esquina
+    pub material_id: usize,
+}
+
+impl Quad {
+    pub fn new(tr: Transform, half_u: f32, half_v: f32, material_id: usize) -> Self {
+        Self { half_u, half_v, tr, uv_scale: 1.0, material_id }
+    }
+    // Desde una esquina y dos aristas (perpendiculares); normal = v x u. None si una arista es nula
+    pub fn from_corner(corner: Vec3, edge_u: Vec3, edge_v: Vec3, material_id: usize) -> Option<Self> {
+        let x = edge_u.normalize();
+        let z = edge_v.normalize();
+        let rot = crate::transform::Mat3 { c0: x, c1: z.cross(x), c2: z };
+        let center = corner + (edge_u + edge_v) * 0.5;
+        Some(Self {
+            half_u: edge_u.length() * 0.5,
+            half_v: edge_v.length() * 0.5,
+            tr: Transform::new(rot, center)?,
+            uv_scale: 1.0,
+            material_id,
+        })
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+        let ro = self.tr.world_to_local_point(r.origin);
+        let rd = self.tr.world_to_local_dir(r.dir);
+        let (t_hit, p_local) = hit_local_xz(ro, rd, tmin, tmax)?;
+        if p_local.x.abs() > self.half_u || p_local.z.abs() > self.half_v { return None; }
+
+        let uv = ((p_local.x + self.half_u) * self.uv_scale, (p_local.z + self.half_v) * self.uv_scale);
+        let n_world = facing(self.tr.local_to_world_normal(Vec3::new(0.0,1.0,0.0)), r.dir);
+        Some(Hit { t: t_hit, p: r.at(t_hit), n: n_world, uv, material_id: self.material_id })
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let (hu, hv) = (self.half_u, self.half_v);
+        let corners = [(-hu,-hv), (hu,-hv), (-hu,hv), (hu,hv)]
+            .map(|(x,z)| self.tr.local_to_world_point(Vec3::new(x, 0.0, z)));
+        // Un poco de grosor para que el slab test no degenere
+        let pad = Vec3::new(1e-4, 1e-4, 1e-4);
+        let b = Aabb::from_points(&corners);
+        Some(Aabb::new(b.min - pad, b.max + pad))
+    }
+}
+
+pub enum Object { Cube(Cube), Sphere(Sphere), Plane(Plane), Quad(Quad), Mesh(Mesh) }
+
+impl Object {
+    pub fn transform_mut(&mut self) -> &mut Transform {
+        match self {
+            Object::Cube(c) => &mut c.tr,
+            Object::Sphere(s) => &mut s.tr,
+            Object::Plane(p) => &mut p.tr,
+            Object::Quad(q) => &mut q.tr,
+            Object::Mesh(m) => &mut m.tr,
+        }
+    }
+}
 
 impl Hittable for Object {
     fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
-        match self { Object::Cube(c) => c.hit(r,tmin,tmax) }
+        match self {
+            Object::Cube(c) => c.hit(r,tmin,tmax),
+            Object::Sphere(s) => s.hit(r,tmin,tmax),
+            Object::Plane(p) => p.hit(r,tmin,tmax),
+            Object::Quad(q) => q.hit(r,tmin,tmax),
+            Object::Mesh(m) => m.hit(r,tmin,tmax),
+        }
+    }
+    fn bounds(&self) -> Option<Aabb> {
+        match self {
+            Object::Cube(c) => c.bounds(),
+            Object::Sphere(s) => s.bounds(),
+            Object::Plane(p) => p.bounds(),
+            Object::Quad(q) => q.bounds(),
+            Object::Mesh(m) => m.bounds(),
+        }
     }
 }
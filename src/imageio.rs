@@ -0,0 +1,37 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+// Escribe un buffer RGBA8 (w*h*4) como PNG, sin pasar por raylib/GL
+pub fn write_png_rgba(path: &str, w: u32, h: u32, rgba: &[u8]) -> Result<(), String> {
+    if let Some(dir) = Path::new(path).parent() && !dir.as_os_str().is_empty() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut enc = png::Encoder::new(BufWriter::new(file), w, h);
+    enc.set_color(png::ColorType::Rgba);
+    enc.set_depth(png::BitDepth::Eight);
+    let mut writer = enc.write_header().map_err(|e| format!("{}: {}", path, e))?;
+    writer.write_image_data(rgba).map_err(|e| format!("{}: {}", path, e))
+}
+
+// Decodifica un PNG a RGBA8 (paleta, gris, 16 bits y sin alfa se expanden)
+pub fn read_png_rgba(path: &str) -> Result<(u32, u32, Vec<u8>), String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut dec = png::Decoder::new(BufReader::new(file));
+    dec.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = dec.read_info().map_err(|e| format!("{}: {}", path, e))?;
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| format!("{}: {}", path, e))?;
+    let (w, h) = (info.width, info.height);
+    let px = &buf[..info.buffer_size()];
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => px.to_vec(),
+        png::ColorType::Rgb => px.chunks_exact(3).flat_map(|c| [c[0], c[1], c[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => px.chunks_exact(2).flat_map(|c| [c[0], c[0], c[0], c[1]]).collect(),
+        png::ColorType::Grayscale => px.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err(format!("{}: paleta sin expandir", path)),
+    };
+    Ok((w, h, rgba))
+}
@@ -0,0 +1,23 @@
+// Núcleo del ray tracer del diorama, sin dependencia de raylib.
+// El viewer interactivo (binario) es el único que enlaza raylib.
+
+pub mod vec3;      pub use vec3::Vec3;
+pub mod ray;       pub use ray::Ray;
+pub mod camera;    pub use camera::{Camera, Bokeh, Projection};
+pub mod controller; pub use controller::{CameraController, CameraMode, ControlInput};
+pub mod filter;    pub use filter::Filter;
+pub mod transform;
+pub mod texture;   pub use texture::TextureAtlas;
+pub mod geometry;  pub use geometry::{Hittable, Hit};
+pub mod mesh;      pub use mesh::{Mesh, Triangle};
+pub mod obj;
+pub mod aabb;      pub use aabb::Aabb;
+pub mod bvh;       pub use bvh::Bvh;
+pub mod sampling;
+pub mod light;     pub use light::Light;
+pub mod brdf;
+pub mod skybox;
+pub mod scene;     pub use scene::Scene;
+pub mod render;    pub use render::{trace_ray, render_to_buffers, render_progressive, schlick_fresnel, RenderSettings, Shading, Integrator, Stereo, Media, Accumulator};
+pub mod pathtracer;
+pub mod imageio;
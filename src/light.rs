@@ -0,0 +1,166 @@
+use crate::vec3::Vec3;
+use crate::sampling::{concentric_disk, orthonormal_basis};
+
+use std::f32::consts::PI;
+
+// Luces de la escena. Cada una se muestrea desde un punto de sombreado y da
+// la dirección hacia la luz, la distancia (para el rayo de sombra) y la radiancia.
+// Las luces de área reciben una muestra `u` en [0,1)² que elige el punto de la luz;
+// promediando varias (estratificadas) se obtienen sombras suaves.
+#[derive(Copy, Clone, Debug)]
+pub enum Light {
+    // Luz puntual con caída suave: intensity * (1 - (d/range)²)², cero a partir de `range`.
+    Point { pos: Vec3, color: Vec3, intensity: f32, range: f32 },
+    // Luz en el infinito (sol/luna): `dir` apunta HACIA la luz; sin caída
+    Directional { dir: Vec3, color: Vec3, intensity: f32 },
+    // Foco: como la puntual, limitada a un cono alrededor de `dir` (sentido de emisión).
+    // Plena dentro de cos_inner, se apaga suavemente hasta cos_outer (penumbra).
+    Spot { pos: Vec3, dir: Vec3, color: Vec3, intensity: f32, range: f32, cos_inner: f32, cos_outer: f32 },
+    // Rectángulo corner + s*edge_u + t*edge_v que emite solo hacia edge_u × edge_v
+    Rect { corner: Vec3, edge_u: Vec3, edge_v: Vec3, color: Vec3, intensity: f32, range: f32 },
+    // Esfera luminosa: cada muestra es un punto del disco que se ve desde `p`
+    Sphere { pos: Vec3, radius: f32, color: Vec3, intensity: f32, range: f32 },
+}
+
+// Caída por distancia compartida por todas las luces locales
+fn window(dist: f32, range: f32) -> f32 {
+    let k = (1.0 - (dist / range).powi(2)).max(0.0);
+    k * k
+}
+
+impl Light {
+    pub fn point(pos: Vec3, color: Vec3, intensity: f32, range: f32) -> Self {
+        Light::Point { pos, color, intensity, range }
+    }
+    pub fn directional(dir: Vec3, color: Vec3, intensity: f32) -> Self {
+        Light::Directional { dir: dir.normalize(), color, intensity }
+    }
+    // `angle`: semiángulo del cono (radianes); `penumbra`: ancho del borde suave, hacia adentro
+    #[allow(clippy::too_many_arguments)]
+    pub fn spot(pos: Vec3, dir: Vec3, color: Vec3, intensity: f32, range: f32, angle: f32, penumbra: f32) -> Self {
+        let cos_outer = angle.cos();
+        let cos_inner = (angle - penumbra.clamp(0.0, angle)).cos();
+        Light::Spot { pos, dir: dir.normalize(), color, intensity, range, cos_inner, cos_outer }
+    }
+
+    pub fn rect(corner: Vec3, edge_u: Vec3, edge_v: Vec3, color: Vec3, intensity: f32, range: f32) -> Self {
+        Light::Rect { corner, edge_u, edge_v, color, intensity, range }
+    }
+    pub fn sphere(pos: Vec3, radius: f32, color: Vec3, intensity: f32, range: f32) -> Self {
+        Light::Sphere { pos, radius, color, intensity, range }
+    }
+
+    // Luces con extensión: necesitan varias muestras para la penumbra
+    pub fn is_area(&self) -> bool { matches!(self, Light::Rect { .. } | Light::Sphere { .. }) }
+
+    // (dirección normalizada hacia la luz, distancia, radiancia); None si no aporta en `p`.
+    // `u` solo se usa en luces de área.
+    pub fn illuminate(&self, p: Vec3, u: (f32, f32)) -> Option<(Vec3, f32, Vec3)> {
+        // Muestra puntual hacia `q` con caída por distancia
+        let toward = |q: Vec3, range: f32| {
+            let to = q - p;
+            let dist = to.length();
+            if dist >= range || dist <= 0.0 { return None; }
+            Some((to * (1.0 / dist), dist, window(dist, range)))
+        };
+        match *self {
+            Light::Point { pos, color, intensity, range } => {
+                let (l, dist, k) = toward(pos, range)?;
+                Some((l, dist, color * (intensity * k)))
+            }
+            Light::Directional { dir, color, intensity } => Some((dir, f32::INFINITY, color * intensity)),
+            Light::Spot { pos, dir, color, intensity, range, cos_inner, cos_outer } => {
+                let (l, dist, k) = toward(pos, range)?;
+                let cos = (-l).dot(dir);
+                if cos <= cos_outer { return None; }
+                // smoothstep entre el borde exterior y el interior del cono
+                let t = ((cos - cos_outer) / (cos_inner - cos_outer).max(1e-6)).min(1.0);
+                let cone = t * t * (3.0 - 2.0 * t);
+                Some((l, dist, color * (intensity * k * cone)))
+            }
+            Light::Rect { .. } | Light::Sphere { .. } => self.emit(p, self.area_point(p, u)),
+        }
+    }
+
+    // Punto de una luz de área elegido por la muestra `u`, visto desde `p`
+    fn area_point(&self, p: Vec3, u: (f32, f32)) -> Vec3 {
+        match *self {
+            Light::Rect { corner, edge_u, edge_v, .. } => corner + edge_u * u.0 + edge_v * u.1,
+            Light::Sphere { pos, radius, .. } => {
+                let (t, b) = orthonormal_basis((p - pos).normalize());
+                let (dx, dy) = concentric_disk(u.0, u.1);
+                pos + (t * dx + b * dy) * radius
+            }
+            Light::Point { pos, .. } | Light::Spot { pos, .. } => pos,
+            Light::Directional { dir, .. } => p + dir,
+        }
+    }
+
+    // Aporte en `p` del punto `q` de una luz de área (mismo formato que `illuminate`)
+    pub fn emit(&self, p: Vec3, q: Vec3) -> Option<(Vec3, f32, Vec3)> {
+        let to = q - p;
+        let dist = to.length();
+        let l = to * (1.0 / dist);
+        match *self {
+            Light::Rect { edge_u, edge_v, color, intensity, range, .. } => {
+                if dist >= range || dist <= 0.0 { return None; }
+                let facing = (-l).dot(edge_u.cross(edge_v).normalize());
+                if facing <= 0.0 { return None; }
+                Some((l, dist, color * (intensity * window(dist, range) * facing)))
+            }
+            Light::Sphere { color, intensity, range, .. } => {
+                if dist >= range || dist <= 0.0 { return None; }
+                Some((l, dist, color * (intensity * window(dist, range))))
+            }
+            _ => None,
+        }
+    }
+
+    // Densidad por ángulo sólido (vista desde `p`) con la que `illuminate` elige el punto `q`.
+    // 0 en las luces puntuales y direccionales: son deltas, ningún rayo las alcanza por azar.
+    pub fn pdf(&self, p: Vec3, q: Vec3) -> f32 {
+        let to = q - p;
+        let d2 = to.dot(to);
+        let l = to * (1.0 / d2.sqrt());
+        let (area, cos) = match *self {
+            Light::Rect { edge_u, edge_v, .. } => {
+                let nrm = edge_u.cross(edge_v);
+                let area = nrm.length();
+                (area, (-l).dot(nrm * (1.0 / area)))
+            }
+            Light::Sphere { pos, radius, .. } => (PI * radius * radius, l.dot((p - pos).normalize()).abs()),
+            _ => return 0.0,
+        };
+        if cos <= 1e-6 { 0.0 } else { d2 / (area * cos) }
+    }
+
+    // Corte del rayo `origin + t·dir` (t < tmax) con la forma que muestrea `illuminate` desde `p`:
+    // el rectángulo, o el disco de la esfera perpendicular a `p` -> centro. Devuelve (t, punto).
+    pub fn intersect(&self, p: Vec3, origin: Vec3, dir: Vec3, tmax: f32) -> Option<(f32, Vec3)> {
+        let (center, nrm) = match *self {
+            Light::Rect { corner, edge_u, edge_v, .. } => (corner, edge_u.cross(edge_v)),
+            Light::Sphere { pos, .. } => (pos, (p - pos).normalize()),
+            _ => return None,
+        };
+        let denom = dir.dot(nrm);
+        if denom.abs() < 1e-8 { return None; }
+        let t = (center - origin).dot(nrm) / denom;
+        if t <= 0.0 || t >= tmax { return None; }
+        let q = origin + dir * t;
+        let inside = match *self {
+            Light::Rect { edge_u, edge_v, .. } => {
+                // coordenadas (s, t) de q en la base (edge_u, edge_v)
+                let d = q - center;
+                let (uu, uv, vv) = (edge_u.dot(edge_u), edge_u.dot(edge_v), edge_v.dot(edge_v));
+                let (du, dv) = (d.dot(edge_u), d.dot(edge_v));
+                let det = uu * vv - uv * uv;
+                let s = (vv * du - uv * dv) / det;
+                let r = (uu * dv - uv * du) / det;
+                (0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&r)
+            }
+            Light::Sphere { radius, .. } => (q - center).length() <= radius,
+            _ => false,
+        };
+        if inside { Some((t, q)) } else { None }
+    }
+}
@@ -1,223 +1,112 @@
-mod vec3;      use vec3::Vec3;
-mod ray;       use ray::Ray;
-mod camera;    use camera::Camera;
-mod transform;
-mod texture;
-mod geometry;  use geometry::{Hittable, Hit};
-mod skybox;
-mod scene;
+mod cli;
+#[cfg(feature = "viewer")]
+mod viewer;
 
-use raylib::prelude::*;
-use raylib::ffi; // UpdateTexture
+use diorama::{Vec3, Camera, CameraController, TextureAtlas};
+use diorama::{scene, imageio, texture, obj};
+use diorama::obj::ObjModel;
+use diorama::render::{render_to_buffers, depth_to_rgba, Integrator, RenderSettings, Stereo};
 
-// ---------------- sombreado/rt ----------------
-fn schlick_fresnel(cosine: f32, ior: f32) -> f32 {
-    let r0 = ((1.0 - ior) / (1.0 + ior)).powi(2);
-    r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+fn load_atlas() -> TextureAtlas {
+    // --- atlas de texturas desde archivos ---
+    std::fs::create_dir_all("assets").ok();
+    TextureAtlas::load_from_paths(&texture::ATLAS_PATHS)
 }
 
-fn trace_ray(r: &Ray, scene: &scene::Scene, depth: u32, atlas: Option<&texture::TextureAtlas>, is_night: bool) -> (Vec3, f32) {
-    const EPS: f32 = 1e-3;
-    const MAX_DIST: f32 = 1000.0;
-
-    let mut closest: Option<Hit> = None;
-    let mut t_max = MAX_DIST;
-
-    for obj in &scene.objects {
-        if let Some(h) = obj.hit(r, EPS, t_max) {
-            t_max = h.t; closest = Some(h);
-        }
-    }
-
-    if let Some(hit) = closest {
-        let m = &scene.materials[hit.material_id]; // <- borrow, no move
-        let base = texture::sample(m.texture, hit.uv, hit.p, atlas);
-
-        // Sombra
-        let light_dir = (scene.light_pos - hit.p).normalize();
-        let shadow_ray = Ray::new(hit.p + hit.n * EPS*10.0, light_dir);
-        let mut in_shadow = false;
-        let dist_light = (scene.light_pos - hit.p).length();
-        let mut tmax = dist_light - EPS;
-        for obj in &scene.objects {
-            if let Some(h) = obj.hit(&shadow_ray, EPS, tmax) {
-                if h.t < dist_light { in_shadow = true; break; }
-                tmax = h.t.min(tmax);
-            }
-        }
-
-        // Phong con colores de luz
-        let mut color = base.hadamard(scene.ambient_color) * scene.ambient;
-        if !in_shadow {
-            let n = hit.n;
-            let l = light_dir;
-            let v = (r.origin - hit.p).normalize();
-            let h = (l + v).normalize();
-            let diff = base.hadamard(scene.light_color) * m.albedo * n.dot(l).max(0.0);
-            let spec = scene.light_color * m.specular * n.dot(h).max(0.0).powf(m.shininess);
-            color = color + diff + spec;
+// Modelos OBJ de `scene::PROPS`; si falta un archivo se avisa y se omite
+fn load_props() -> Vec<(ObjModel, Vec3, f32)> {
+    let dir = std::path::Path::new("assets");
+    scene::PROPS.iter().filter_map(|&(file, pos, rot_y)| {
+        let path = dir.join(file);
+        match obj::load_obj(&path.to_string_lossy()) {
+            Ok(model) => Some((model, pos, rot_y)),
+            Err(e) => { eprintln!("(aviso) No se pudo cargar '{}': {}", path.display(), e); None }
         }
+    }).collect()
+}
 
-        if depth == 0 { return (color.clamp01(), hit.t); }
+// Órbita inicial alrededor del centro del diorama, según --yaw/--pitch/--dist
+fn initial_controller(opts: &cli::Options) -> CameraController {
+    CameraController::orbit(Vec3::new(0.0,0.5,0.0), opts.yaw_deg.to_radians(), opts.pitch_deg.to_radians(), opts.cam_dist)
+}
 
-        // Reflexión / Refracción
-        let view_dir = (-r.dir).normalize();
-        let cosi = view_dir.dot(hit.n).max(0.0);
-        let fresnel = schlick_fresnel(cosi, m.ior);
+// Render offline: sin ventana ni contexto GL, directo a PNG
+fn run_headless(opts: &cli::Options, out: &str) {
+    let (width, height) = (opts.width, opts.height);
+    let atlas = load_atlas();
+    let settings = opts.render_settings();
 
-        if m.reflectivity > 0.0 {
-            let refl_dir = Vec3::reflect(r.dir, hit.n).normalize();
-            let refl_ray = Ray::new(hit.p + hit.n * EPS*10.0, refl_dir);
-            let (refl_col, _) = trace_ray(&refl_ray, scene, depth-1, atlas, is_night);
-            color = color*(1.0 - m.reflectivity) + refl_col * m.reflectivity;
-        }
+    let mut pixels = vec![0u8; (width*height*4) as usize];
+    let mut depthbuf = vec![f32::INFINITY; (width*height) as usize];
 
-        if m.transparency > 0.0 {
-            let mut n = hit.n;
-            let mut eta = 1.0 / m.ior;
-            let cosi2 = (-r.dir).dot(n);
-            if cosi2 < 0.0 { // dentro
-                n = n * -1.0;
-                eta = m.ior;
-            }
-            if let Some(refr_dir) = Vec3::refract(r.dir, n, eta) {
-                let refr_ray = Ray::new(hit.p - n * EPS*10.0, refr_dir.normalize());
-                let (refr_col, _) = trace_ray(&refr_ray, scene, depth-1, atlas, is_night);
-                let kr = fresnel;
-                color = color*(1.0 - m.transparency) + (refr_col*(1.0-kr) + color*kr) * m.transparency;
-            }
+    let sc = scene::Scene::diorama_with_mode(0.0, opts.is_night, load_props());
+    let mut cam = initial_controller(opts).camera(opts.fov, width as f32 / height as f32);
+    cam.bokeh = opts.bokeh;
+    cam.projection = opts.projection;
+    cam.fisheye_deg = opts.fisheye_deg;
+    if let Some(d) = opts.iod { cam.iod = d; }
+    if let Some(d) = opts.convergence { cam.convergence = d; }
+    if let Some(h) = opts.view_height { cam.view_height = h; }
+    if let Some(f) = opts.focus {
+        cam.focus_dist = f;
+    } else if opts.aperture > 0.0 {
+        // Autofoco: pasada pinhole barata y mono (en estéreo el centro cae en la unión de los ojos) solo para tener el depth buffer
+        let quick = RenderSettings { samples: 1, max_depth: 0, light_samples: 1, ao_samples: 0, integrator: Integrator::Whitted,
+                                     stereo: Stereo::Mono, ..settings };
+        render_to_buffers(width, height, &cam, &sc, &mut pixels, &mut depthbuf, Some(&atlas), opts.is_night, &quick);
+        match Camera::focus_from_depth(&depthbuf, width, height) {
+            Some(f) => { cam.focus_dist = f; eprintln!("Autofoco a {:.2}", f); }
+            None => eprintln!("aviso: autofoco sin superficie en el centro; se enfoca el centro del diorama"),
         }
-
-        (color.clamp01(), hit.t)
-    } else {
-        (skybox::sample_with_mode(r.dir, is_night), MAX_DIST)
     }
-}
-
-// Render -> RGBA y Depth
-fn render_to_buffers(width: u32, height: u32, cam: &Camera, scene: &scene::Scene, rgba: &mut [u8], depth: &mut [f32], atlas: Option<&texture::TextureAtlas>, is_night: bool) {
-    let gamma = 1.0/2.2;
-    let mut i = 0usize;
+    cam.aperture = opts.aperture;
+    sc.bvh.stats.reset(); // que la pasada de autofoco no cuente
 
-    for y in 0..height {
-        for x in 0..width {
-            let ray = cam.ray_for_pixel(x, y, width, height);
-            let (col, t) = trace_ray(&ray, scene, 5, atlas, is_night);
+    let t0 = std::time::Instant::now();
+    render_to_buffers(width, height, &cam, &sc, &mut pixels, &mut depthbuf, Some(&atlas), opts.is_night, &settings);
+    eprintln!("Render {}x{} ({} spp) en {:.2?}", width, height, opts.spp, t0.elapsed());
+    eprintln!("{}", sc.bvh.stats.report(sc.objects.len()));
 
-            let r = (col.x.clamp(0.0,1.0).powf(gamma) * 255.0) as u8;
-            let g = (col.y.clamp(0.0,1.0).powf(gamma) * 255.0) as u8;
-            let b = (col.z.clamp(0.0,1.0).powf(gamma) * 255.0) as u8;
-
-            rgba[i]   = r;
-            rgba[i+1] = g;
-            rgba[i+2] = b;
-            rgba[i+3] = 255;
-            depth[(y*width + x) as usize] = t;
-            i += 4;
-        }
+    if let Err(e) = imageio::write_png_rgba(out, width, height, &pixels) {
+        eprintln!("error: no se pudo guardar el PNG: {}", e);
+        std::process::exit(1);
     }
-}
-
-fn depth_to_rgba(depth: &[f32], near: f32, far: f32, rgba: &mut [u8]) {
-    let mut i = 0usize;
-    for &d in depth {
-        let mut z = if d.is_finite() { (d-near)/(far-near) } else { 1.0 };
-        z = z.clamp(0.0,1.0);
-        let v = ((1.0 - z) * 255.0) as u8;
-        rgba[i] = v; rgba[i+1] = v; rgba[i+2] = v; rgba[i+3] = 255;
-        i += 4;
+    eprintln!("Guardado '{}'", out);
+
+    if let Some(dpath) = &opts.depth_output {
+        let mut tmp = vec![0u8; pixels.len()];
+        depth_to_rgba(&depthbuf, 0.1, 50.0, &mut tmp);
+        if let Err(e) = imageio::write_png_rgba(dpath, width, height, &tmp) {
+            eprintln!("error: no se pudo guardar el depth: {}", e);
+            std::process::exit(1);
+        }
+        eprintln!("Guardado '{}'", dpath);
     }
-}
 
-// Actualiza la textura vía FFI (robusto entre versiones)
-fn update_texture_rgba(tex: &mut Texture2D, pixels: &[u8]) {
-    unsafe {
-        ffi::UpdateTexture(*tex.as_ref(), pixels.as_ptr() as *const std::ffi::c_void);
+    if let Some(apath) = &opts.ao_output {
+        let ao_settings = RenderSettings { integrator: Integrator::AmbientOcclusion, ..settings };
+        render_to_buffers(width, height, &cam, &sc, &mut pixels, &mut depthbuf, Some(&atlas), opts.is_night, &ao_settings);
+        if let Err(e) = imageio::write_png_rgba(apath, width, height, &pixels) {
+            eprintln!("error: no se pudo guardar la oclusión ambiental: {}", e);
+            std::process::exit(1);
+        }
+        eprintln!("Guardado '{}'", apath);
     }
 }
 
 fn main() {
-    let width: i32 = std::env::var("W").ok().and_then(|s| s.parse().ok()).unwrap_or(800);
-    let height: i32 = std::env::var("H").ok().and_then(|s| s.parse().ok()).unwrap_or(450);
-
-    let (mut rl, thread) = raylib::init()
-        .size(width, height)
-        .title("Diorama Raytracer (Rust + raylib)")
-        .build();
-
-    rl.set_target_fps(30);
-
-    // --- atlas de texturas desde archivos ---
-    std::fs::create_dir_all("assets").ok();
-    let atlas_paths = [
-        "assets/grass.png", // id 0
-        "assets/dirt.png",  // id 1
-        "assets/stone.png", // id 2
-        "assets/metal2.png", // id 3
-        "assets/glass.png", // id 4
-        "assets/water.png", // id 5
-    ];
-    let atlas = texture::TextureAtlas::load_from_paths(&atlas_paths);
-
-    // Buffers
-    let mut pixels = vec![0u8; (width*height*4) as usize];
-    let mut depthbuf = vec![f32::INFINITY; (width*height) as usize];
-
-    // Texture destino
-    let blank = Image::gen_image_color(width, height, Color::BLACK);
-    let mut tex = rl.load_texture_from_image(&thread, &blank).unwrap();
-
-    // Estado
-    let mut yaw: f32 = 35f32.to_radians();
-    let mut cam_dist: f32 = 8.5;
-    let mut fov: f32 = 60.0;
-    let mut auto_rotate = true;
-    let mut show_depth = false;
-    let mut is_night = true;  // Empezar en modo nocturno para ver la luna
-
-    while !rl.window_should_close() {
-        // input
-        if rl.is_key_down(KeyboardKey::KEY_A) { yaw -= 0.02; }
-        if rl.is_key_down(KeyboardKey::KEY_D) { yaw += 0.02; }
-        if rl.is_key_down(KeyboardKey::KEY_W) { cam_dist -= 0.05; }
-        if rl.is_key_down(KeyboardKey::KEY_S) { cam_dist += 0.05; }
-        cam_dist -= rl.get_mouse_wheel_move() * 0.2;
-        if rl.is_key_pressed(KeyboardKey::KEY_R) { auto_rotate = !auto_rotate; }
-        if rl.is_key_pressed(KeyboardKey::KEY_Z) { show_depth = !show_depth; }
-        if rl.is_key_pressed(KeyboardKey::KEY_N) { is_night = !is_night; }  // Toggle día/noche
-        if rl.is_key_pressed(KeyboardKey::KEY_Q) { fov = (fov-1.0).clamp(25.0, 90.0); }
-        if rl.is_key_pressed(KeyboardKey::KEY_E) { fov = (fov+1.0).clamp(25.0, 90.0); }
-        if rl.is_key_pressed(KeyboardKey::KEY_P) { rl.take_screenshot(&thread, "out/frame.png"); } // <- con thread
-
-        if auto_rotate { yaw += 0.01; }
-
-        // escena + cámara
-        let sc = scene::Scene::diorama_with_mode(yaw, is_night);
-        let aspect = width as f32 / height as f32;
-        let cam_pos = Vec3::new(0.0, 2.5, cam_dist);
-        let cam = Camera { pos: cam_pos, target: Vec3::new(0.0,0.5,0.0), up: Vec3::new(0.0,1.0,0.0), fov_deg: fov, aspect };
+    let opts = cli::Options::from_args();
+    if let Some(out) = &opts.output {
+        run_headless(&opts, out);
+        return;
+    }
 
-        // render
-        render_to_buffers(width as u32, height as u32, &cam, &sc, &mut pixels, &mut depthbuf, Some(&atlas), is_night);
-        if show_depth {
-            let mut tmp = vec![0u8; pixels.len()];
-            depth_to_rgba(&depthbuf, 0.1, 50.0, &mut tmp);
-            update_texture_rgba(&mut tex, &tmp);
-        } else {
-            update_texture_rgba(&mut tex, &pixels);
-        }
+    #[cfg(feature = "viewer")]
+    viewer::run(&opts);
 
-        // draw
-        let mut d = rl.begin_drawing(&thread);
-        d.clear_background(Color::BLACK);
-        d.draw_texture(&tex, 0, 0, Color::WHITE);
-        d.draw_text(
-            &format!("A/D rotar | Wheel/W/S zoom | R auto:{} | Z depth:{} | N {}| Q/E FOV:{:.0} | P screenshot",
-                     if auto_rotate {"ON"} else {"OFF"},
-                     if show_depth {"ON"} else {"OFF"},
-                     if is_night {"NOCHE"} else {"DÍA"},
-                     fov),
-            10, 10, 18, Color::WHITE
-        );
+    #[cfg(not(feature = "viewer"))]
+    {
+        eprintln!("error: compilado sin la feature 'viewer' (raylib); usa --out <archivo.png> para render offline");
+        std::process::exit(2);
     }
 }
@@ -0,0 +1,94 @@
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::geometry::{Hittable, Hit};
+use crate::transform::Transform;
+
+// Triángulo con normales y UV por vértice (espacio local de la malla)
+#[derive(Copy, Clone)]
+pub struct Triangle {
+    pub p: [Vec3; 3],
+    pub n: [Vec3; 3],
+    pub uv: [(f32,f32); 3],
+    pub material_id: usize,
+}
+
+impl Triangle {
+    // Sin normales por vértice: se usa la normal de la cara (sombreado plano)
+    pub fn flat(p: [Vec3; 3], uv: [(f32,f32); 3], material_id: usize) -> Self {
+        let n = (p[1] - p[0]).cross(p[2] - p[0]).normalize();
+        Self { p, n: [n, n, n], uv, material_id }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+        // Möller–Trumbore
+        let e1 = self.p[1] - self.p[0];
+        let e2 = self.p[2] - self.p[0];
+        let pv = r.dir.cross(e2);
+        let det = e1.dot(pv);
+        if det.abs() < 1e-10 { return None; }
+        let inv_det = 1.0 / det;
+        let tv = r.origin - self.p[0];
+        let b1 = tv.dot(pv) * inv_det;
+        if !(0.0..=1.0).contains(&b1) { return None; }
+        let qv = tv.cross(e1);
+        let b2 = r.dir.dot(qv) * inv_det;
+        if b2 < 0.0 || b1 + b2 > 1.0 { return None; }
+        let t = e2.dot(qv) * inv_det;
+        if t < tmin || t > tmax { return None; }
+
+        // Interpolación baricéntrica de normal y UV
+        let b0 = 1.0 - b1 - b2;
+        let n = (self.n[0] * b0 + self.n[1] * b1 + self.n[2] * b2).normalize();
+        let uv = (
+            self.uv[0].0 * b0 + self.uv[1].0 * b1 + self.uv[2].0 * b2,
+            self.uv[0].1 * b0 + self.uv[1].1 * b1 + self.uv[2].1 * b2,
+        );
+        Some(Hit { t, p: r.at(t), n, uv, material_id: self.material_id })
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let b = Aabb::from_points(&self.p);
+        let pad = Vec3::new(1e-5, 1e-5, 1e-5);
+        Some(Aabb::new(b.min - pad, b.max + pad))
+    }
+}
+
+// Malla de triángulos con su propio BVH (en espacio local) y un Transform
+pub struct Mesh {
+    pub tris: Vec<Triangle>,
+    pub bvh: Bvh,
+    pub tr: Transform,
+    local_bounds: Aabb,
+}
+
+impl Mesh {
+    pub fn new(tris: Vec<Triangle>, tr: Transform) -> Self {
+        let bvh = Bvh::build(&tris);
+        let local_bounds = tris.iter().fold(Aabb::EMPTY, |b, t| b.union(t.bounds().unwrap()));
+        Self { tris, bvh, tr, local_bounds }
+    }
+}
+
+impl Hittable for Mesh {
+    fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+        // Rayo en espacio local; la dirección no se renormaliza para conservar t
+        let local = Ray { origin: self.tr.world_to_local_point(r.origin), dir: self.tr.world_to_local_dir(r.dir) };
+        let h = self.bvh.hit(&self.tris, &local, tmin, tmax)?;
+        let n_world = self.tr.local_to_world_normal(h.n);
+        Some(Hit { t: h.t, p: r.at(h.t), n: n_world, uv: h.uv, material_id: h.material_id })
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        if self.local_bounds.is_empty() { return None; }
+        let (mn, mx) = (self.local_bounds.min, self.local_bounds.max);
+        let corners: Vec<Vec3> = (0..8).map(|i| {
+            let c = Vec3::new(if i&1==0 { mn.x } else { mx.x }, if i&2==0 { mn.y } else { mx.y }, if i&4==0 { mn.z } else { mx.z });
+            self.tr.local_to_world_point(c)
+        }).collect();
+        Some(Aabb::from_points(&corners))
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::vec3::Vec3;
+use crate::mesh::{Mesh, Triangle};
+use crate::scene::Material;
+use crate::texture::{self, TextureKind};
+use crate::transform::Transform;
+
+// Cargador Wavefront OBJ (+ MTL).
+// Soporta v/vt/vn, caras poligonales (en abanico), índices negativos, usemtl/mtllib
+// y grupos de suavizado: sin `vn`, las caras de un grupo `s n` reciben normales
+// promediadas por vértice dentro del grupo; sin `s` (o con `s off`), normal de cara.
+
+pub struct ObjMaterial {
+    pub name: String,
+    pub kd: Vec3,
+    pub ks: Vec3,
+    pub ke: Vec3, // emisión
+    pub ns: f32,
+    pub d: f32,
+    pub ni: f32,
+    pub map_kd: Option<String>, // ruta resuelta respecto al .mtl
+}
+
+impl ObjMaterial {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), kd: Vec3::new(0.8, 0.8, 0.8), ks: Vec3::ZERO, ke: Vec3::ZERO, ns: 10.0, d: 1.0, ni: 1.0, map_kd: None }
+    }
+}
+
+pub struct ObjModel {
+    pub tris: Vec<Triangle>, // material_id = índice en `materials`
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjModel {
+    // Registra los materiales en la escena y devuelve la malla lista para un Object::Mesh.
+    // map_Kd se resuelve contra el atlas del diorama; si no está, se usa Kd.
+    pub fn into_mesh(self, tr: Transform, materials: &mut Vec<Material>) -> Mesh {
+        let base = materials.len();
+        for m in &self.materials {
+            let texture = match m.map_kd.as_deref().map(|p| (p, texture::atlas_id(p))) {
+                Some((_, Some(id))) => TextureKind::Image { id },
+                Some((p, None)) => {
+                    eprintln!("(aviso) '{}' no está en el atlas; uso Kd para '{}'", p, m.name);
+                    TextureKind::Solid(m.kd)
+                }
+                None => TextureKind::Solid(m.kd),
+            };
+            materials.push(Material {
+                name: m.name.clone(),
+                texture,
+                albedo: 0.9,
+                specular: (m.ks.x + m.ks.y + m.ks.z) / 3.0,
+                shininess: m.ns.max(1.0),
+                // Sin PBR en el .mtl: rugosidad equivalente al exponente Ns (Blinn-Phong)
+                metallic: 0.0,
+                roughness: (2.0 / (m.ns.max(0.0) + 2.0)).sqrt(),
+                transparency: (1.0 - m.d).clamp(0.0, 1.0),
+                reflectivity: 0.0,
+                ior: if m.ni > 1.0 { m.ni } else { 1.5 }, // Ni ausente (1.0): dieléctrico típico
+                emission: m.ke,
+                absorption: Vec3::ZERO,
+            });
+        }
+        let tris = self.tris.into_iter().map(|t| Triangle { material_id: base + t.material_id, ..t }).collect();
+        Mesh::new(tris, tr)
+    }
+}
+
+fn parse_f32s<'a>(it: impl Iterator<Item = &'a str>, line: usize) -> Result<Vec<f32>, String> {
+    it.map(|s| s.parse::<f32>().map_err(|_| format!("línea {}: número inválido '{}'", line, s))).collect()
+}
+
+fn vec3(v: &[f32], line: usize) -> Result<Vec3, String> {
+    if v.len() < 3 { return Err(format!("línea {}: se esperaban 3 componentes", line)); }
+    Ok(Vec3::new(v[0], v[1], v[2]))
+}
+
+// Índice OBJ (1-based, negativo = relativo al final) -> 0-based
+fn resolve(idx: &str, len: usize, line: usize) -> Result<usize, String> {
+    let i: i64 = idx.parse().map_err(|_| format!("línea {}: índice inválido '{}'", line, idx))?;
+    let r = if i > 0 { i - 1 } else { len as i64 + i };
+    if r < 0 || r >= len as i64 { return Err(format!("línea {}: índice fuera de rango {}", line, i)); }
+    Ok(r as usize)
+}
+
+pub fn load_mtl(path: &Path) -> Result<Vec<ObjMaterial>, String> {
+    let src = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut mats: Vec<ObjMaterial> = Vec::new();
+
+    for (ln, raw) in src.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        let mut it = line.split_whitespace();
+        let Some(key) = it.next() else { continue };
+        if key == "newmtl" {
+            mats.push(ObjMaterial::new(it.next().unwrap_or("")));
+            continue;
+        }
+        let Some(m) = mats.last_mut() else { continue };
+        match key {
+            "Kd" => m.kd = vec3(&parse_f32s(it, ln + 1)?, ln + 1)?,
+            "Ks" => m.ks = vec3(&parse_f32s(it, ln + 1)?, ln + 1)?,
+            "Ke" => m.ke = vec3(&parse_f32s(it, ln + 1)?, ln + 1)?,
+            "Ns" => m.ns = parse_f32s(it, ln + 1)?.first().copied().unwrap_or(m.ns),
+            "Ni" => m.ni = parse_f32s(it, ln + 1)?.first().copied().unwrap_or(m.ni),
+            "d"  => m.d = parse_f32s(it, ln + 1)?.first().copied().unwrap_or(m.d),
+            "Tr" => m.d = 1.0 - parse_f32s(it, ln + 1)?.first().copied().unwrap_or(0.0),
+            // map_Kd [opciones] archivo: el archivo es el último token
+            "map_Kd" => m.map_kd = line.split_whitespace().last().map(|f| dir.join(f).to_string_lossy().into_owned()),
+            _ => {}
+        }
+    }
+    Ok(mats)
+}
+
+pub fn load_obj(path: &str) -> Result<ObjModel, String> {
+    let src = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+
+    let mut pos: Vec<Vec3> = Vec::new();
+    let mut uvs: Vec<(f32,f32)> = Vec::new();
+    let mut nrm: Vec<Vec3> = Vec::new();
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+    let mut current: Option<usize> = None;
+    let mut smooth: Option<u32> = None; // grupo de suavizado activo
+
+    // (índices de posición, triángulo, grupo) para los que no traen `vn`
+    let mut pending: Vec<([usize; 3], usize, Option<u32>)> = Vec::new();
+    let mut tris: Vec<Triangle> = Vec::new();
+
+    for (ln, raw) in src.lines().enumerate() {
+        let ln = ln + 1;
+        let line = raw.split('#').next().unwrap_or("").trim();
+        let mut it = line.split_whitespace();
+        let Some(key) = it.next() else { continue };
+        match key {
+            "v"  => pos.push(vec3(&parse_f32s(it, ln)?, ln)?),
+            "vn" => nrm.push(vec3(&parse_f32s(it, ln)?, ln)?.normalize()),
+            "vt" => {
+                let v = parse_f32s(it, ln)?;
+                // OBJ tiene v hacia arriba; las imágenes del atlas, fila 0 arriba
+                uvs.push((v.first().copied().unwrap_or(0.0), 1.0 - v.get(1).copied().unwrap_or(0.0)));
+            }
+            "s" => smooth = it.next().and_then(|g| g.parse::<u32>().ok()).filter(|&g| g > 0),
+            "mtllib" => {
+                for f in it {
+                    match load_mtl(&dir.join(f)) {
+                        Ok(m) => materials.extend(m),
+                        Err(e) => eprintln!("(aviso) No se pudo cargar mtllib: {}", e),
+                    }
+                }
+            }
+            "usemtl" => {
+                let name = it.next().unwrap_or("");
+                current = Some(match materials.iter().position(|m| m.name == name) {
+                    Some(i) => i,
+                    None => { materials.push(ObjMaterial::new(name)); materials.len() - 1 }
+                });
+            }
+            "f" => {
+                let mat = *current.get_or_insert_with(|| { materials.push(ObjMaterial::new("default")); materials.len() - 1 });
+                let mut corners = Vec::new();
+                for tok in it {
+                    let mut parts = tok.split('/');
+                    let vi = resolve(parts.next().unwrap_or(""), pos.len(), ln)?;
+                    let ti = match parts.next() { Some(s) if !s.is_empty() => Some(resolve(s, uvs.len(), ln)?), _ => None };
+                    let ni = match parts.next() { Some(s) if !s.is_empty() => Some(resolve(s, nrm.len(), ln)?), _ => None };
+                    corners.push((vi, ti, ni));
+                }
+                if corners.len() < 3 { return Err(format!("línea {}: cara con menos de 3 vértices", ln)); }
+
+                for k in 1..corners.len() - 1 {
+                    let c = [corners[0], corners[k], corners[k + 1]];
+                    let p = c.map(|(v, _, _)| pos[v]);
+                    let uv = c.map(|(_, t, _)| t.map(|t| uvs[t]).unwrap_or((0.0, 0.0)));
+                    let mut tri = Triangle::flat(p, uv, mat);
+                    if c.iter().all(|(_, _, n)| n.is_some()) {
+                        tri.n = c.map(|(_, _, n)| nrm[n.unwrap()]);
+                    } else {
+                        pending.push((c.map(|(v, _, _)| v), tris.len(), smooth));
+                    }
+                    tris.push(tri);
+                }
+            }
+            _ => {} // o, g, l, ... se ignoran
+        }
+    }
+
+    // Normales suavizadas: promedio (ponderado por área) de las caras del mismo grupo que comparten vértice
+    let mut acc: HashMap<(usize, u32), Vec3> = HashMap::new();
+    for &(v, ti, s) in &pending {
+        let Some(g) = s else { continue };
+        let t = &tris[ti];
+        let face = (t.p[1] - t.p[0]).cross(t.p[2] - t.p[0]);
+        for &i in &v { let a = acc.entry((i, g)).or_insert(Vec3::ZERO); *a = *a + face; }
+    }
+    for &(v, ti, s) in &pending {
+        if let Some(g) = s { tris[ti].n = v.map(|i| acc[&(i, g)].normalize()); }
+    }
+
+    Ok(ObjModel { tris, materials })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(name: &str, src: &str) -> ObjModel {
+        let path = std::env::temp_dir().join(format!("diorama_test_{}_{}.obj", name, std::process::id()));
+        std::fs::write(&path, src).unwrap();
+        let model = load_obj(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        model.unwrap()
+    }
+
+    const QUAD: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n";
+
+    #[test]
+    fn negative_indices_and_fan() {
+        let m = load("fan", &format!("{}v 0.5 1.5 0\nf 1 2 3 5 4\nf -5 -4 -3\n", QUAD));
+        assert_eq!(m.tris.len(), 4);
+        // Abanico desde el primer vértice: (0,1,2), (0,2,4), (0,4,3)
+        let p = |i: usize, k: usize| m.tris[i].p[k];
+        assert_eq!([p(0, 0), p(0, 1), p(0, 2)], [Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0)]);
+        assert_eq!([p(1, 1), p(1, 2)], [Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.5, 1.5, 0.0)]);
+        assert_eq!([p(2, 1), p(2, 2)], [Vec3::new(0.5, 1.5, 0.0), Vec3::new(0.0, 1.0, 0.0)]);
+        // -5 es el primer vértice de los cinco
+        assert_eq!([p(3, 0), p(3, 1), p(3, 2)], [p(0, 0), p(0, 1), p(0, 2)]);
+        assert!(load_obj("/no/existe.obj").is_err());
+    }
+
+    // Dos caras en ángulo recto que comparten la arista (0,0,0)-(1,0,0)
+    const HINGE: &str = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\nv 1 0 1\n";
+
+    #[test]
+    fn smoothing_groups() {
+        let flat = |m: &ObjModel| m.tris.iter().all(|t| {
+            let f = (t.p[1] - t.p[0]).cross(t.p[2] - t.p[0]).normalize();
+            t.n.iter().all(|n| (*n - f).length() < 1e-5)
+        });
+        // Sin `s` y con grupos distintos: normales de cara
+        assert!(flat(&load("nos", &format!("{}f 1 2 3 4\nf 2 1 5 6\n", HINGE))));
+        assert!(flat(&load("s12", &format!("{}s 1\nf 1 2 3 4\ns 2\nf 2 1 5 6\n", HINGE))));
+        // Mismo grupo: la arista compartida promedia las dos caras
+        let m = load("s11", &format!("{}s 1\nf 1 2 3 4\nf 2 1 5 6\n", HINGE));
+        assert!(!flat(&m));
+        let n = m.tris[0].n[0]; // caras con normales +Z y +Y
+        assert!(n.x.abs() < 1e-5 && n.y > 0.1 && n.z > 0.1);
+    }
+}
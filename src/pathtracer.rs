@@ -0,0 +1,220 @@
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::scene::Scene;
+use crate::brdf;
+use crate::texture::{self, TextureAtlas};
+use crate::skybox;
+use crate::sampling::{self, Rng};
+use crate::render::{Interface, Media, RenderSettings};
+
+use std::f32::consts::PI;
+
+// Path tracer con NEE y MIS (heurística de la potencia) para las luces de área; los rebotes
+// difusos/rugosos atraviesan los emisores hasta la luz de adentro. Devuelve (radiancia sin recortar, distancia)
+pub fn trace_path(r: &Ray, scene: &Scene, atlas: Option<&TextureAtlas>, is_night: bool, settings: &RenderSettings, rng: &mut Rng) -> (Vec3, f32) {
+    const EPS: f32 = 1e-3;
+    const MAX_DIST: f32 = 1000.0;
+    const SMOOTH: f32 = 0.03;
+    const RR_START: u32 = 3; // rebotes antes de la ruleta rusa
+    const MAX_SKIPS: u32 = 8;
+
+    let mut ray = *r;
+    let mut throughput = Vec3::ONE;
+    let mut radiance = Vec3::ZERO;
+    let mut media = Media::AIR;
+    let mut first_t = MAX_DIST;
+    // Vértice anterior y densidad BRDF del rebote que generó `ray` (0 = especular/cámara)
+    let mut prev_p = ray.origin;
+    let mut prev_pdf = 0.0f32;
+    let mut bounce = 0u32;
+    let mut skips = 0u32;
+
+    loop {
+        let hit = scene.hit(&ray, EPS, MAX_DIST);
+        let t_hit = hit.as_ref().map_or(MAX_DIST, |h| h.t);
+
+        // Luces de área alcanzadas por un rebote difuso/rugoso, pesadas contra la NEE;
+        // el disco de la esfera se orienta hacia `prev_p`, no hacia el origen tras atravesar un emisor
+        if prev_pdf > 0.0 {
+            for light in scene.lights.iter().filter(|l| l.is_area()) {
+                let Some((_, q)) = light.intersect(prev_p, ray.origin, ray.dir, t_hit) else { continue };
+                let Some((_, _, le)) = light.emit(prev_p, q) else { continue };
+                let pl = light.pdf(prev_p, q);
+                let w = prev_pdf * prev_pdf / (pl * pl + prev_pdf * prev_pdf);
+                radiance = radiance + throughput.hadamard(le) * (PI * pl * w);
+            }
+        }
+
+        let Some(hit) = hit else {
+            // Tras un rebote difuso/rugoso el sol/la luna ya llegaron por la NEE de su luz direccional
+            let sky = if prev_pdf > 0.0 { skybox::sample_without_glow(ray.dir, is_night) } else { skybox::sample_with_mode(ray.dir, is_night) };
+            radiance = radiance + throughput.hadamard(sky);
+            break;
+        };
+        if bounce == 0 && skips == 0 { first_t = hit.t; }
+
+        // Beer–Lambert en el tramo recorrido dentro del medio actual
+        if let Some(a) = media.current().map(|i| scene.materials[i].absorption) && a.x + a.y + a.z > 0.0 {
+            throughput = throughput.hadamard(Vec3::new((-a.x * hit.t).exp(), (-a.y * hit.t).exp(), (-a.z * hit.t).exp()));
+        }
+
+        let m = &scene.materials[hit.material_id];
+        if m.is_emissive() {
+            if prev_pdf > 0.0 && skips < MAX_SKIPS {
+                ray = Ray::new(hit.p + ray.dir * EPS, ray.dir);
+                skips += 1;
+                continue;
+            }
+            radiance = radiance + throughput.hadamard(m.emission);
+        }
+        skips = 0;
+
+        let base = texture::sample(m.texture, hit.uv, hit.p, atlas);
+        let d = ray.dir;
+        let it = Interface::new(d, &hit, scene, media);
+        let n = it.n;
+        let v = -d;
+        let nv = v.dot(n).max(1e-4);
+
+        // Probabilidad de cada lóbulo según su peso aproximado
+        let tr = m.transparency * (1.0 - m.metallic);
+        let kr = brdf::fresnel_schlick_roughness(nv, brdf::f0(base, m.metallic, m.ior), m.roughness);
+        let w_spec = (kr.x + kr.y + kr.z) / 3.0 * (1.0 - tr);
+        let w_diff = (base.x + base.y + base.z) / 3.0 * (1.0 - m.metallic) * (1.0 - tr);
+        let total = w_spec + w_diff + tr;
+        if total <= 0.0 { break; }
+        let (p_diff, p_spec, p_tr) = (w_diff / total, w_spec / total, tr / total);
+        let bsdf_pdf = |l: Vec3| {
+            let nl = n.dot(l).max(0.0);
+            p_diff * nl / PI + if p_spec > 0.0 { p_spec * brdf::ggx_pdf(n, v, l, m.roughness) } else { 0.0 }
+        };
+
+        // Next-event estimation (solo la parte no especular)
+        if p_diff + p_spec > 0.0 {
+            for light in &scene.lights {
+                let u = (rng.next_f32(), rng.next_f32());
+                let Some((l, dist_light, le)) = light.illuminate(hit.p, u) else { continue };
+                let nl = n.dot(l);
+                if nl <= 0.0 { continue; }
+                let shadow_ray = Ray::new(hit.p + n * EPS*10.0, l);
+                let trans = scene.transmittance(&shadow_ray, EPS, dist_light - EPS, atlas);
+                if trans.x + trans.y + trans.z <= 0.0 { continue; }
+                let mut w = 1.0;
+                if light.is_area() {
+                    let pl = light.pdf(hit.p, hit.p + l * dist_light);
+                    let pb = bsdf_pdf(l);
+                    w = pl * pl / (pl * pl + pb * pb);
+                }
+                let f = brdf::eval(m, base, n, v, l);
+                radiance = radiance + throughput.hadamard(f.hadamard(le.hadamard(trans))) * (nl * w);
+            }
+        }
+
+        if bounce >= settings.max_bounces { break; }
+
+        // Siguiente dirección
+        let xi = rng.next_f32();
+        let u = (rng.next_f32(), rng.next_f32());
+        if xi < p_tr {
+            // Dieléctrico: reflejo o refracción elegidos con probabilidad de Fresnel (exacto)
+            let mut h = if m.roughness > SMOOTH { brdf::sample_ggx_normal(n, m.roughness, u) } else { n };
+            if d.dot(h) >= 0.0 { h = n; }
+            let f = brdf::fresnel_dielectric((-d).dot(h), it.n1, it.n2);
+            let refr = Vec3::refract(d, h, it.n1 / it.n2).filter(|t| t.dot(n) < 0.0);
+            match refr {
+                Some(t) if rng.next_f32() >= f => {
+                    ray = Ray::new(hit.p - n * EPS*10.0, t);
+                    media = it.media_t;
+                }
+                _ => {
+                    let mut l = Vec3::reflect(d, h);
+                    if l.dot(n) <= 0.0 { l = Vec3::reflect(d, n); }
+                    ray = Ray::new(hit.p + n * EPS*10.0, l);
+                }
+            }
+            throughput = throughput * (tr / p_tr);
+            prev_pdf = 0.0;
+        } else {
+            let l = if xi < p_tr + p_diff { sampling::cosine_hemisphere(n, u) }
+                    else { Vec3::reflect(d, brdf::sample_ggx_normal(n, m.roughness, u)) };
+            let nl = n.dot(l);
+            if nl <= 0.0 { break; }
+            let pb = bsdf_pdf(l);
+            if pb <= 0.0 { break; }
+            // eval da π·f
+            throughput = throughput.hadamard(brdf::eval(m, base, n, v, l)) * (nl / (PI * pb));
+            ray = Ray::new(hit.p + n * EPS*10.0, l);
+            prev_pdf = pb;
+        }
+        prev_p = hit.p;
+        bounce += 1;
+
+        // Ruleta rusa: los caminos que ya aportan poco terminan antes, sin sesgo
+        if bounce >= RR_START {
+            let q = throughput.x.max(throughput.y).max(throughput.z).clamp(0.05, 0.95);
+            if rng.next_f32() >= q { break; }
+            throughput = throughput * (1.0 / q);
+        }
+    }
+    (radiance, first_t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bvh::Bvh;
+    use crate::geometry::{Object, Plane, Sphere};
+    use crate::light::Light;
+    use crate::scene::Material;
+    use crate::texture::TextureKind;
+
+    fn material(color: Vec3, metallic: f32) -> Material {
+        Material { name: String::new(), texture: TextureKind::Solid(color), albedo: 0.9, specular: 0.0, shininess: 1.0, metallic,
+                   roughness: 1.0, transparency: 0.0, reflectivity: 0.0, ior: 1.5, emission: Vec3::ZERO, absorption: Vec3::ZERO }
+    }
+
+    // Suelo gris dentro de una esfera negra sin reflejo (los caminos terminan ahí): solo luz directa
+    fn room(light: Light) -> Scene {
+        let objects = vec![
+            Object::Plane(Plane::new(Vec3::ZERO, Vec3::new(0.0, 1.0, 0.0), 0).unwrap()),
+            Object::Sphere(Sphere::new(Vec3::ZERO, 20.0, 1)),
+        ];
+        Scene { bvh: Bvh::build(&objects), objects, materials: vec![material(Vec3::new(0.6, 0.6, 0.6), 0.0), material(Vec3::ZERO, 1.0)],
+                lights: vec![light], night_lights: vec![], ambient: 0.0, ambient_color: Vec3::ZERO }
+    }
+
+    // Promedio de caminos con un rebote contra NEE pura en el mismo punto
+    fn check(light: Light) {
+        let scene = room(light);
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0));
+        let settings = RenderSettings { max_bounces: 1, ..RenderSettings::default() };
+        let n = 40_000;
+
+        let mut rng = Rng::new(1);
+        let mut pt = Vec3::ZERO;
+        for _ in 0..n { pt = pt + trace_path(&ray, &scene, None, false, &settings, &mut rng).0; }
+        let pt = pt.x / n as f32;
+
+        let hit = scene.hit(&ray, 1e-3, 100.0).unwrap();
+        let m = &scene.materials[0];
+        let base = texture::sample(m.texture, hit.uv, hit.p, None);
+        let mut nee = 0.0;
+        for i in 0..n {
+            let u = sampling::stratified(i, n, &mut rng);
+            let Some((l, _, le)) = light.illuminate(hit.p, u) else { continue };
+            let nl = hit.n.dot(l);
+            if nl > 0.0 { nee += brdf::eval(m, base, hit.n, -ray.dir, l).hadamard(le).x * nl; }
+        }
+        let nee = nee / n as f32;
+        assert!((pt - nee).abs() < 0.03 * nee, "path tracer {} vs NEE {}", pt, nee);
+    }
+
+    #[test]
+    fn mis_matches_light_sampling() {
+        let white = Vec3::ONE;
+        check(Light::rect(Vec3::new(-0.1, 1.0, -0.1), Vec3::new(0.2, 0.0, 0.0), Vec3::new(0.0, 0.0, 0.2), white, 2.0, 10.0));
+        check(Light::rect(Vec3::new(-2.0, 1.0, -2.0), Vec3::new(4.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 4.0), white, 0.3, 10.0));
+        check(Light::sphere(Vec3::new(0.3, 0.8, -0.2), 0.1, white, 2.0, 10.0));
+        check(Light::sphere(Vec3::new(0.3, 1.5, -0.2), 1.0, white, 0.3, 10.0));
+    }
+}
@@ -0,0 +1,556 @@
+use crate::vec3::Vec3;
+use crate::ray::Ray;
+use crate::camera::Camera;
+use crate::filter::Filter;
+use crate::scene::{Scene, Material};
+use crate::geometry::Hit;
+use crate::brdf;
+use crate::texture::{self, TextureAtlas};
+use crate::skybox;
+use crate::sampling::{self, Rng};
+use crate::pathtracer;
+
+use std::sync::Mutex;
+
+// ---------------- sombreado/rt ----------------
+pub fn schlick_fresnel(cosine: f32, ior: f32) -> f32 {
+    let r0 = ((1.0 - ior) / (1.0 + ior)).powi(2);
+    r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+}
+
+// `media`: medios transparentes en los que viaja el rayo (Media::AIR para rayos de cámara).
+// Si el rayo viaja dentro de un material con `absorption`, el color que trae se atenúa
+// con la distancia recorrida (Beer–Lambert): exp(-σ·t) por canal.
+#[allow(clippy::too_many_arguments)]
+pub fn trace_ray(r: &Ray, scene: &Scene, depth: u32, atlas: Option<&TextureAtlas>, is_night: bool, settings: &RenderSettings, rng: &mut Rng, media: Media) -> (Vec3, f32) {
+    let (col, t) = shade(r, scene, depth, atlas, is_night, settings, rng, media);
+    match media.current().map(|i| scene.materials[i].absorption) {
+        Some(a) if a.x + a.y + a.z > 0.0 => (col.hadamard(Vec3::new((-a.x * t).exp(), (-a.y * t).exp(), (-a.z * t).exp())), t),
+        _ => (col, t),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn shade(r: &Ray, scene: &Scene, depth: u32, atlas: Option<&TextureAtlas>, is_night: bool, settings: &RenderSettings, rng: &mut Rng, media: Media) -> (Vec3, f32) {
+    const EPS: f32 = 1e-3;
+    const MAX_DIST: f32 = 1000.0;
+
+    if let Some(hit) = scene.hit(r, EPS, MAX_DIST) {
+        let m = &scene.materials[hit.material_id]; // <- borrow, no move
+        let base = texture::sample(m.texture, hit.uv, hit.p, atlas);
+        let pbr = settings.shading == Shading::Pbr;
+
+        // Ambiente + emisión propia + aporte de cada luz (con su rayo de sombra;
+        // los transparentes en el camino dejan pasar luz teñida).
+        // Las luces de área se muestrean `light_samples` veces (estratificado) y se promedian.
+        // El ambiente se oscurece en rincones y junto a paredes según la oclusión ambiental
+        let ao_samples = if depth == settings.max_depth { settings.ao_samples } else { settings.ao_samples.min(1) };
+        let ao_n = if hit.n.dot(r.dir) > 0.0 { -hit.n } else { hit.n };
+        let ao = ambient_occlusion(scene, hit.p, ao_n, settings.ao_radius, ao_samples, rng);
+        let ambient = base.hadamard(scene.ambient_color) * (scene.ambient * ao);
+        let mut color = m.emission + if pbr { ambient * ((1.0 - m.metallic) * (1.0 - m.transparency)) } else { ambient };
+        let n = hit.n;
+        let v = (r.origin - hit.p).normalize();
+        for light in &scene.lights {
+            let samples = if light.is_area() { settings.light_samples.max(1) } else { 1 };
+            let mut acc = Vec3::ZERO;
+            for s in 0..samples {
+                let u = sampling::stratified(s, samples, rng);
+                let Some((l, dist_light, radiance)) = light.illuminate(hit.p, u) else { continue };
+                if n.dot(l) <= 0.0 { continue; }
+                let shadow_ray = Ray::new(hit.p + n * EPS*10.0, l);
+                let trans = scene.transmittance(&shadow_ray, EPS, dist_light - EPS, atlas);
+                if trans.x + trans.y + trans.z <= 0.0 { continue; }
+                let radiance = radiance.hadamard(trans);
+                if pbr {
+                    acc = acc + brdf::eval(m, base, n, v, l).hadamard(radiance) * n.dot(l);
+                } else {
+                    let h = (l + v).normalize();
+                    let diff = base.hadamard(radiance) * m.albedo * n.dot(l);
+                    let spec = radiance * m.specular * n.dot(h).max(0.0).powf(m.shininess);
+                    acc = acc + diff + spec;
+                }
+            }
+            color = color + acc * (1.0 / samples as f32);
+        }
+
+        if depth == 0 { return (color.clamp01(), hit.t); }
+
+        if pbr { return (shade_pbr_secondary(r, &hit, m, base, color, scene, depth, atlas, is_night, settings, rng, media).clamp01(), hit.t); }
+
+        // Reflexión / Refracción
+        if m.reflectivity > 0.0 {
+            let refl_dir = Vec3::reflect(r.dir, hit.n).normalize();
+            let refl_ray = Ray::new(hit.p + hit.n * EPS*10.0, refl_dir);
+            let (refl_col, _) = trace_ray(&refl_ray, scene, depth-1, atlas, is_night, settings, rng, media);
+            color = color*(1.0 - m.reflectivity) + refl_col * m.reflectivity;
+        }
+
+        // Dieléctrico: reflejo y refracción pesados por Fresnel; en reflexión total interna, solo reflejo
+        if m.transparency > 0.0 {
+            let d = r.dir.normalize();
+            let it = Interface::new(d, &hit, scene, media);
+            let kr = brdf::fresnel_dielectric((-d).dot(it.n), it.n1, it.n2);
+            let refl_ray = Ray::new(hit.p + it.n * EPS*10.0, Vec3::reflect(d, it.n).normalize());
+            let (refl_col, _) = trace_ray(&refl_ray, scene, depth-1, atlas, is_night, settings, rng, media);
+            let mut mix = refl_col * kr;
+            if kr < 1.0 && let Some(refr_dir) = Vec3::refract(d, it.n, it.n1 / it.n2) {
+                let refr_ray = Ray::new(hit.p - it.n * EPS*10.0, refr_dir.normalize());
+                let (refr_col, _) = trace_ray(&refr_ray, scene, depth-1, atlas, is_night, settings, rng, it.media_t);
+                mix = mix + refr_col * (1.0 - kr);
+            }
+            color = color*(1.0 - m.transparency) + mix * m.transparency;
+        }
+
+        (color.clamp01(), hit.t)
+    } else {
+        (skybox::sample_with_mode(r.dir, is_night), MAX_DIST)
+    }
+}
+
+// Fracción del hemisferio de `n` sin obstáculos a menos de `radius` (1 = a cielo abierto),
+// con `samples` direcciones coseno estratificadas; 0 muestras = sin oclusión.
+// Los emisores no ocluyen, igual que en las sombras.
+pub fn ambient_occlusion(scene: &Scene, p: Vec3, n: Vec3, radius: f32, samples: u32, rng: &mut Rng) -> f32 {
+    const EPS: f32 = 1e-3;
+    if samples == 0 || radius <= 0.0 { return 1.0; }
+    let mut open = 0;
+    for s in 0..samples {
+        let dir = sampling::cosine_hemisphere(n, sampling::stratified(s, samples, rng));
+        if !scene.occluded(&Ray::new(p + n * EPS*10.0, dir), EPS, radius) { open += 1; }
+    }
+    open as f32 / samples as f32
+}
+
+// Pasada de oclusión ambiental: gris según la oclusión en el primer impacto, blanco en el cielo
+fn ao_pass(r: &Ray, scene: &Scene, settings: &RenderSettings, rng: &mut Rng) -> (Vec3, f32) {
+    const EPS: f32 = 1e-3;
+    const MAX_DIST: f32 = 1000.0;
+    match scene.hit(r, EPS, MAX_DIST) {
+        Some(hit) => {
+            let n = if hit.n.dot(r.dir) > 0.0 { -hit.n } else { hit.n };
+            let ao = ambient_occlusion(scene, hit.p, n, settings.ao_radius, settings.ao_samples.max(1), rng);
+            (Vec3::new(ao, ao, ao), hit.t)
+        }
+        None => (Vec3::ONE, MAX_DIST),
+    }
+}
+
+// Medios transparentes que contienen al rayo (ids de material, el último es el actual).
+// Permite anidar: agua dentro de vidrio, o un rayo que sale del agua al vidrio y no al aire.
+#[derive(Copy, Clone, Debug)]
+pub struct Media { ids: [u32; 4], len: usize }
+
+impl Media {
+    pub const AIR: Media = Media { ids: [0; 4], len: 0 };
+
+    pub fn current(&self) -> Option<usize> { self.len.checked_sub(1).map(|i| self.ids[i] as usize) }
+    pub fn ior(&self, scene: &Scene) -> f32 { self.current().map_or(1.0, |i| scene.materials[i].ior) }
+    // Más de 4 niveles de anidamiento se ignoran
+    pub fn entering(mut self, id: usize) -> Self {
+        if self.len < self.ids.len() { self.ids[self.len] = id as u32; self.len += 1; }
+        self
+    }
+    pub fn exiting(mut self, id: usize) -> Self {
+        if let Some(k) = self.ids[..self.len].iter().rposition(|&i| i as usize == id) {
+            self.ids.copy_within(k + 1..self.len, k);
+            self.len -= 1;
+        }
+        self
+    }
+}
+
+// Interfaz en un material transparente: normal del lado por el que llega el rayo,
+// índices de refracción n1 (origen) -> n2 (destino) y medios del rayo refractado
+pub(crate) struct Interface { pub n: Vec3, pub n1: f32, pub n2: f32, pub media_t: Media }
+
+impl Interface {
+    pub fn new(d: Vec3, hit: &Hit, scene: &Scene, media: Media) -> Self {
+        let id = hit.material_id;
+        let ior = scene.materials[id].ior;
+        if d.dot(hit.n) < 0.0 {
+            let media_t = media.entering(id);
+            Self { n: hit.n, n1: media.ior(scene), n2: ior, media_t }
+        } else {
+            let media_t = media.exiting(id);
+            Self { n: -hit.n, n1: ior, n2: media_t.ior(scene), media_t }
+        }
+    }
+}
+
+// Reflexión/refracción del modo PBR: Fresnel exacto en la parte transparente, Schlick en la opaca;
+// con rugosidad, `gloss_samples` normales de microfaceta GGX en el primer rebote y 1 en los demás
+#[allow(clippy::too_many_arguments)]
+fn shade_pbr_secondary(r: &Ray, hit: &Hit, m: &Material, base: Vec3, direct: Vec3, scene: &Scene, depth: u32,
+                       atlas: Option<&TextureAtlas>, is_night: bool, settings: &RenderSettings, rng: &mut Rng, media: Media) -> Vec3 {
+    const EPS: f32 = 1e-3;
+    const ROUGH_NO_REFLECT: f32 = 0.6;
+    const SMOOTH: f32 = 0.03; // por debajo: espejo perfecto, un solo rayo
+
+    let d = r.dir.normalize();
+    let it = Interface::new(d, hit, scene, media);
+    let n = it.n;
+    let cos = (-d).dot(n).max(0.0);
+    let kr = brdf::fresnel_schlick_roughness(cos, brdf::f0(base, m.metallic, m.ior), m.roughness);
+
+    let reflects = m.metallic > 0.0 || m.roughness < ROUGH_NO_REFLECT || m.transparency > 0.0;
+    let refracts = m.transparency > 0.0 && m.metallic < 1.0;
+    if !reflects && !refracts { return direct; }
+    let tr = if refracts { m.transparency * (1.0 - m.metallic) } else { 0.0 };
+
+    let rough = m.roughness > SMOOTH;
+    let samples = if rough && depth == settings.max_depth { settings.gloss_samples.max(1) } else { 1 };
+    let mut acc = Vec3::ZERO;
+    for s in 0..samples {
+        let mut h = if rough { brdf::sample_ggx_normal(n, m.roughness, sampling::stratified(s, samples, rng)) } else { n };
+        if d.dot(h) >= 0.0 { h = n; } // microfaceta de espaldas al rayo
+
+        let mut refl_w = kr * (1.0 - tr);
+        if refracts {
+            let f = brdf::fresnel_dielectric((-d).dot(h), it.n1, it.n2);
+            refl_w = refl_w + Vec3::new(f, f, f) * tr;
+            if f < 1.0 && let Some(refr_dir) = Vec3::refract(d, h, it.n1 / it.n2).filter(|t| t.dot(n) < 0.0) {
+                let refr_ray = Ray::new(hit.p - n * EPS*10.0, refr_dir.normalize());
+                let (refr_col, _) = trace_ray(&refr_ray, scene, depth-1, atlas, is_night, settings, rng, it.media_t);
+                acc = acc + refr_col * ((1.0 - f) * tr);
+            } else if f < 1.0 {
+                refl_w = refl_w + Vec3::new(1.0 - f, 1.0 - f, 1.0 - f) * tr; // la microfaceta no deja pasar: se refleja
+            }
+        }
+        if reflects {
+            let mut refl_dir = Vec3::reflect(d, h);
+            if refl_dir.dot(n) <= 0.0 { refl_dir = Vec3::reflect(d, n); } // no atravesar la superficie
+            let refl_ray = Ray::new(hit.p + n * EPS*10.0, refl_dir.normalize());
+            let (refl_col, _) = trace_ray(&refl_ray, scene, depth-1, atlas, is_night, settings, rng, media);
+            acc = acc + refl_col.hadamard(refl_w);
+        }
+    }
+    direct + acc * (1.0 / samples as f32)
+}
+
+// Modelo de sombreado: GGX metálico/rugosidad (por defecto) o el Phong original
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shading { Pbr, Phong }
+
+// Integrador: Whitted (recursivo, ambiente con oclusión), path tracing con iluminación global,
+// o solo la pasada de oclusión ambiental
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Integrator { Whitted, PathTracer, AmbientOcclusion }
+
+// Composición estéreo del frame: un solo ojo, los dos lado a lado o uno sobre otro
+// (cada ojo comprimido a media imagen, como esperan las pantallas 3D), o anaglifo
+// rojo/cian (rojo del ojo izquierdo, verde y azul del derecho)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stereo { Mono, SideBySide, OverUnder, Anaglyph }
+
+impl Stereo {
+    // Siguiente modo (tecla del viewer)
+    pub fn next(self) -> Self {
+        match self { Stereo::Mono => Stereo::SideBySide, Stereo::SideBySide => Stereo::OverUnder, Stereo::OverUnder => Stereo::Anaglyph, Stereo::Anaglyph => Stereo::Mono }
+    }
+    pub fn name(self) -> &'static str {
+        match self { Stereo::Mono => "mono", Stereo::SideBySide => "sbs", Stereo::OverUnder => "ou", Stereo::Anaglyph => "anaglyph" }
+    }
+}
+
+impl std::str::FromStr for Stereo {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "mono" => Ok(Stereo::Mono),
+            "sbs" => Ok(Stereo::SideBySide),
+            "ou" => Ok(Stereo::OverUnder),
+            "anaglyph" => Ok(Stereo::Anaglyph),
+            _ => Err(format!("modo estéreo desconocido '{}'", s)),
+        }
+    }
+}
+
+// Parámetros del render (independientes de la escena)
+#[derive(Copy, Clone, Debug)]
+pub struct RenderSettings {
+    pub max_depth: u32,  // rebotes de reflexión/refracción
+    pub threads: usize,  // 0 = todos los núcleos disponibles
+    pub tile_rows: u32,  // alto (en filas) de cada tile
+    pub light_samples: u32, // rayos de sombra por luz de área (estratificados)
+    pub frame: u32,      // semilla de las muestras; variarla entre frames y promediar converge
+    pub shading: Shading,
+    pub gloss_samples: u32, // direcciones por reflejo/refracción rugosa en el primer rebote
+    pub integrator: Integrator,
+    pub max_bounces: u32, // rebotes del path tracer (la ruleta rusa corta antes los caminos oscuros)
+    pub ao_samples: u32, // rayos de oclusión ambiental en el primer impacto (1 en los demás; 0 = ambiente plano)
+    pub ao_radius: f32,  // distancia a la que un obstáculo deja de ocluir
+    pub samples: u32,    // muestras por píxel y por frame (estratificadas dentro del filtro)
+    pub filter: Filter,  // filtro de reconstrucción del anti-aliasing
+    pub stereo: Stereo,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self { Self { max_depth: 5, threads: 0, tile_rows: 8, light_samples: 4, frame: 0, shading: Shading::Pbr, gloss_samples: 4,
+                           integrator: Integrator::Whitted, max_bounces: 8, ao_samples: 4, ao_radius: 0.6,
+                           samples: 1, filter: Filter::Box, stereo: Stereo::Mono } }
+}
+
+impl RenderSettings {
+    pub fn worker_count(&self) -> usize {
+        if self.threads > 0 { self.threads }
+        else { std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1) }
+    }
+}
+
+// Radiancia de un rayo de cámara con el integrador elegido
+fn radiance(ray: &Ray, scene: &Scene, atlas: Option<&TextureAtlas>, is_night: bool, settings: &RenderSettings, rng: &mut Rng) -> (Vec3, f32) {
+    match settings.integrator {
+        Integrator::Whitted => trace_ray(ray, scene, settings.max_depth, atlas, is_night, settings, rng, Media::AIR),
+        Integrator::PathTracer => pathtracer::trace_path(ray, scene, atlas, is_night, settings, rng),
+        Integrator::AmbientOcclusion => ao_pass(ray, scene, settings, rng),
+    }
+}
+
+// Recorta a [0,1] y aplica gamma
+fn write_rgba8(col: Vec3, out: &mut [u8]) {
+    let gamma = 1.0/2.2;
+    out[0] = (col.x.clamp(0.0,1.0).powf(gamma) * 255.0) as u8;
+    out[1] = (col.y.clamp(0.0,1.0).powf(gamma) * 255.0) as u8;
+    out[2] = (col.z.clamp(0.0,1.0).powf(gamma) * 255.0) as u8;
+    out[3] = 255;
+}
+
+// Traza las filas [y0, y0 + rows) y entrega, por píxel, (índice en el tile, suma de muestras, distancia).
+// Las `samples` muestras se estratifican y se reparten según el filtro;
+// la distancia es la de la muestra más cercana al centro.
+#[allow(clippy::too_many_arguments)]
+fn render_rows(y0: u32, rows: u32, width: u32, height: u32, cam: &Camera, scene: &Scene, atlas: Option<&TextureAtlas>, is_night: bool,
+               settings: &RenderSettings, mut put: impl FnMut(usize, PixelSum, f32)) {
+    let samples = settings.samples.max(1);
+    let filter = settings.filter.sampler();
+    for y in y0..y0 + rows {
+        for x in 0..width {
+            let mut rng = Rng::for_pixel(x, y, settings.frame);
+            let mut px = PixelSum::default();
+            let (mut t_best, mut d_best) = (f32::INFINITY, f32::INFINITY);
+            for s in 0..samples {
+                let (dx, dy, w) = filter.sample(sampling::stratified(s, samples, &mut rng));
+                let lens = if cam.aperture > 0.0 { (rng.next_f32(), rng.next_f32()) } else { (0.5, 0.5) };
+                let (col, t) = eye_radiance(cam, x as f32 + 0.5 + dx, y as f32 + 0.5 + dy, width, height, lens, scene, atlas, is_night, settings, &mut rng);
+                // una muestra NaN/infinita arruinaría el promedio: se descarta
+                if !(col.x + col.y + col.z).is_finite() { continue; }
+                px.add(col, w);
+                if dx * dx + dy * dy < d_best { d_best = dx * dx + dy * dy; t_best = t; }
+            }
+            put(((y-y0)*width + x) as usize, px, t_best);
+        }
+    }
+}
+
+// Radiancia en el punto (px, py) del frame según el modo estéreo: en mono, la cámara sin más;
+// lado a lado / arriba-abajo, el ojo de esa mitad con coordenadas locales a ella; anaglifo,
+// los dos ojos en el mismo punto. La distancia es la del ojo trazado (el izquierdo en anaglifo).
+#[allow(clippy::too_many_arguments)]
+fn eye_radiance(cam: &Camera, px: f32, py: f32, width: u32, height: u32, lens: (f32, f32), scene: &Scene, atlas: Option<&TextureAtlas>,
+                is_night: bool, settings: &RenderSettings, rng: &mut Rng) -> (Vec3, f32) {
+    let mut trace = |cam: &Camera, px: f32, py: f32, w: u32, h: u32| match cam.ray_through(px, py, w, h, lens) {
+        Some(ray) => radiance(&ray, scene, atlas, is_night, settings, rng),
+        None => (Vec3::ZERO, f32::INFINITY), // fuera de la imagen del ojo de pez
+    };
+    let (hw, hh) = ((width / 2).max(1), (height / 2).max(1));
+    let eyes = [cam.for_eye(-1.0), cam.for_eye(1.0)];
+    match settings.stereo {
+        Stereo::Mono => trace(cam, px, py, width, height),
+        Stereo::SideBySide => {
+            let k = (px >= hw as f32) as usize;
+            trace(&eyes[k], px - (k as u32 * hw) as f32, py, hw, height)
+        }
+        Stereo::OverUnder => {
+            let k = (py >= hh as f32) as usize;
+            trace(&eyes[k], px, py - (k as u32 * hh) as f32, width, hh)
+        }
+        Stereo::Anaglyph => {
+            let (l, t) = trace(&eyes[0], px, py, width, height);
+            let (r, _) = trace(&eyes[1], px, py, width, height);
+            (Vec3::new(l.x, r.y, r.z), t)
+        }
+    }
+}
+
+// Muestras de un píxel: Σ w·color y Σ w con w = ±1 (signo del filtro), más la suma sin pesar
+#[derive(Copy, Clone, Default)]
+pub struct PixelSum { pub sum: Vec3, pub weight: f32, pub plain: Vec3, pub count: u32 }
+
+impl PixelSum {
+    fn add(&mut self, col: Vec3, w: f32) {
+        self.sum = self.sum + col * w;
+        self.weight += w;
+        self.plain = self.plain + col;
+        self.count += 1;
+    }
+    fn merge(&mut self, o: PixelSum) {
+        self.sum = self.sum + o.sum;
+        self.weight += o.weight;
+        self.plain = self.plain + o.plain;
+        self.count += o.count;
+    }
+    // Promedio pesado; si los lóbulos negativos anulan el peso, el promedio simple
+    pub fn resolve(&self) -> Vec3 {
+        if self.weight > 0.0 { self.sum * (1.0 / self.weight) }
+        else if self.count > 0 { self.plain * (1.0 / self.count as f32) }
+        else { Vec3::ZERO }
+    }
+}
+
+// Reparte los tiles entre `workers` hilos que los toman de una cola;
+// cada tile escribe en slices disjuntos, así el resultado es idéntico al de un solo hilo.
+fn run_tiles<T: Send>(tiles: impl Iterator<Item = T> + Send, workers: usize, render: impl Fn(T) + Sync) {
+    if workers <= 1 {
+        tiles.for_each(render);
+        return;
+    }
+    let queue = Mutex::new(tiles);
+    std::thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                let Some(tile) = next else { break };
+                render(tile);
+            });
+        }
+    });
+}
+
+// Render -> RGBA y Depth (un frame de `settings.samples` muestras por píxel)
+#[allow(clippy::too_many_arguments)]
+pub fn render_to_buffers(width: u32, height: u32, cam: &Camera, scene: &Scene, rgba: &mut [u8], depth: &mut [f32], atlas: Option<&TextureAtlas>, is_night: bool, settings: &RenderSettings) {
+    let rows = settings.tile_rows.max(1) as usize;
+    let w = width as usize;
+    let n = w * height as usize;
+
+    let tiles = rgba[..n*4].chunks_mut(w*rows*4)
+        .zip(depth[..n].chunks_mut(w*rows))
+        .enumerate()
+        .map(|(k, (c, d))| ((k*rows) as u32, c, d));
+
+    let workers = settings.worker_count().min(n.div_ceil(w*rows).max(1));
+    run_tiles(tiles, workers, |(y0, c, d)| {
+        render_rows(y0, d.len() as u32 / width, width, height, cam, scene, atlas, is_night, settings, |i, px, t| {
+            write_rgba8(px.resolve(), &mut c[i*4..i*4+4]);
+            d[i] = t;
+        });
+        scene.bvh.stats.flush();
+    });
+}
+
+// Suma de radiancias por píxel a lo largo de varios frames (render progresivo).
+// Mientras la cámara y el modo no cambian, cada frame agrega `settings.samples` muestras
+// nuevas por píxel y la imagen converge; llamar `reset()` cuando cambian.
+pub struct Accumulator {
+    pub sum: Vec<PixelSum>,
+    pub frames: u32,
+}
+
+impl Accumulator {
+    pub fn new(width: u32, height: u32) -> Self {
+        let n = (width*height) as usize;
+        Self { sum: vec![PixelSum::default(); n], frames: 0 }
+    }
+    pub fn reset(&mut self) {
+        self.sum.fill(PixelSum::default());
+        self.frames = 0;
+    }
+}
+
+// Agrega un frame (semilla `settings.frame + acc.frames`) y escribe el promedio pesado en RGBA y Depth
+#[allow(clippy::too_many_arguments)]
+pub fn render_progressive(width: u32, height: u32, cam: &Camera, scene: &Scene, acc: &mut Accumulator, rgba: &mut [u8], depth: &mut [f32],
+                          atlas: Option<&TextureAtlas>, is_night: bool, settings: &RenderSettings) {
+    let rows = settings.tile_rows.max(1) as usize;
+    let w = width as usize;
+    let n = w * height as usize;
+    let settings = RenderSettings { frame: settings.frame.wrapping_add(acc.frames), ..*settings };
+
+    let tiles = acc.sum[..n].chunks_mut(w*rows)
+        .zip(rgba[..n*4].chunks_mut(w*rows*4))
+        .zip(depth[..n].chunks_mut(w*rows))
+        .enumerate()
+        .map(|(k, ((a, c), d))| ((k*rows) as u32, a, c, d));
+
+    let workers = settings.worker_count().min(n.div_ceil(w*rows).max(1));
+    run_tiles(tiles, workers, |(y0, a, c, d)| {
+        render_rows(y0, d.len() as u32 / width, width, height, cam, scene, atlas, is_night, &settings, |i, px, t| {
+            a[i].merge(px);
+            write_rgba8(a[i].resolve(), &mut c[i*4..i*4+4]);
+            d[i] = t;
+        });
+        scene.bvh.stats.flush();
+    });
+    acc.frames += 1;
+}
+
+// Distancia de foco desde la cámara mono (el ojo central) con rayos pinhole por los 5×5 píxeles
+// del centro; no depende del modo estéreo ni del último frame
+pub fn autofocus(cam: &Camera, scene: &Scene, width: u32, height: u32) -> Option<f32> {
+    let (cx, cy) = (width / 2, height / 2);
+    let mut depth = [f32::INFINITY; 25];
+    for (i, d) in depth.iter_mut().enumerate() {
+        let (x, y) = ((cx + (i % 5) as u32).saturating_sub(2), (cy + (i / 5) as u32).saturating_sub(2));
+        if let Some(ray) = cam.ray_for_pixel(x, y, width, height) && let Some(h) = scene.hit(&ray, 1e-3, 1000.0) { *d = h.t; }
+    }
+    Camera::focus_from_depth(&depth, 5, 5)
+}
+
+// Depth buffer -> gris (cerca = claro). `depth` guarda la distancia del primer impacto a lo
+// largo del rayo; en proyección paralela se mide desde el plano de la imagen, a la misma
+// distancia del target que la cámara, así `near`/`far` sirven para todas las proyecciones.
+pub fn depth_to_rgba(depth: &[f32], near: f32, far: f32, rgba: &mut [u8]) {
+    let mut i = 0usize;
+    for &d in depth {
+        let mut z = if d.is_finite() { (d-near)/(far-near) } else { 1.0 };
+        z = z.clamp(0.0,1.0);
+        let v = ((1.0 - z) * 255.0) as u8;
+        rgba[i] = v; rgba[i+1] = v; rgba[i+2] = v; rgba[i+3] = 255;
+        i += 4;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(cam: &Camera, scene: &Scene, w: u32, h: u32, settings: &RenderSettings) -> (Vec<u8>, Vec<f32>) {
+        let (mut rgba, mut depth) = (vec![0u8; (w*h*4) as usize], vec![0f32; (w*h) as usize]);
+        render_to_buffers(w, h, cam, scene, &mut rgba, &mut depth, None, false, settings);
+        (rgba, depth)
+    }
+
+    #[test]
+    fn mitchell_one_sample_is_not_black() {
+        // Mirando al cielo cada píxel tiene color; una muestra en un lóbulo negativo no lo anula
+        let scene = Scene::diorama(0.0);
+        let cam = Camera::look_at(Vec3::new(0.0, 30.0, 0.0), Vec3::new(0.0, 40.0, 0.5), 60.0, 1.0);
+        let settings = RenderSettings { filter: Filter::Mitchell, samples: 1, ..RenderSettings::default() };
+        let (rgba, _) = render(&cam, &scene, 16, 16, &settings);
+        assert!(rgba.chunks(4).all(|p| p[0] as u32 + p[1] as u32 + p[2] as u32 > 0));
+    }
+
+    #[test]
+    fn threads_do_not_change_the_image() {
+        let scene = Scene::diorama(0.0);
+        let cam = Camera::look_at(Vec3::new(3.0, 2.5, 4.0), Vec3::new(0.0, 0.5, 0.0), 60.0, 4.0 / 3.0);
+        let one = RenderSettings { threads: 1, tile_rows: 3, samples: 2, ..RenderSettings::default() };
+        let four = RenderSettings { threads: 4, ..one };
+        let (a, da) = render(&cam, &scene, 40, 30, &one);
+        let (b, db) = render(&cam, &scene, 40, 30, &four);
+        assert!(a == b);
+        assert!(da.iter().zip(&db).all(|(x, y)| x.to_bits() == y.to_bits()));
+    }
+
+    #[test]
+    fn autofocus_matches_mono_depth() {
+        let scene = Scene::diorama(0.0);
+        let cam = Camera::look_at(Vec3::new(3.0, 2.5, 4.0), Vec3::new(0.0, 0.5, 0.0), 60.0, 16.0 / 9.0);
+        let mono = RenderSettings { max_depth: 0, ..RenderSettings::default() };
+        let (_, depth) = render(&cam, &scene, 64, 36, &mono);
+        let f = autofocus(&cam, &scene, 64, 36);
+        let (f, g) = (f.unwrap(), Camera::focus_from_depth(&depth, 64, 36).unwrap());
+        assert!((f - g).abs() < 0.02 * g, "sonda {} vs depth buffer {}", f, g);
+    }
+}
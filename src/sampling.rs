@@ -0,0 +1,73 @@
+// Números aleatorios y patrones de muestreo para el render.
+// Cada píxel tiene su propio generador, sembrado con (x, y, frame): el resultado no
+// depende del orden de los hilos y frames distintos dan muestras independientes.
+
+use crate::vec3::Vec3;
+
+// Hash de enteros (PCG)
+fn pcg(v: u32) -> u32 {
+    let state = v.wrapping_mul(747796405).wrapping_add(2891336453);
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+    (word >> 22) ^ word
+}
+
+pub struct Rng { state: u32 }
+
+impl Rng {
+    pub fn new(seed: u32) -> Self { Self { state: pcg(seed) } }
+    pub fn for_pixel(x: u32, y: u32, frame: u32) -> Self {
+        Self::new(pcg(x ^ pcg(y ^ pcg(frame))))
+    }
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = pcg(self.state);
+        self.state
+    }
+    // Uniforme en [0, 1)
+    pub fn next_f32(&mut self) -> f32 { (self.next_u32() >> 8) as f32 * (1.0 / 16_777_216.0) }
+}
+
+// Muestra i de n estratificada en [0,1)²: una celda de una grilla de ~√n × √n, con jitter
+pub fn stratified(i: u32, n: u32, rng: &mut Rng) -> (f32, f32) {
+    let cols = (n as f32).sqrt().ceil().max(1.0) as u32;
+    let rows = n.div_ceil(cols).max(1);
+    let (cx, cy) = (i % cols, (i / cols) % rows);
+    ((cx as f32 + rng.next_f32()) / cols as f32, (cy as f32 + rng.next_f32()) / rows as f32)
+}
+
+// [0,1)² -> disco unidad (mapeo concéntrico de Shirley, conserva la estratificación)
+pub fn concentric_disk(u: f32, v: f32) -> (f32, f32) {
+    let (a, b) = (2.0 * u - 1.0, 2.0 * v - 1.0);
+    if a == 0.0 && b == 0.0 { return (0.0, 0.0); }
+    let q = std::f32::consts::FRAC_PI_4;
+    let (r, phi) = if a.abs() > b.abs() { (a, q * (b / a)) } else { (b, 2.0 * q - q * (a / b)) };
+    (r * phi.cos(), r * phi.sin())
+}
+
+// Dos vectores unitarios perpendiculares a `n` (unitario) y entre sí (Duff et al. 2017)
+pub fn orthonormal_basis(n: Vec3) -> (Vec3, Vec3) {
+    let sign = 1f32.copysign(n.z);
+    let a = -1.0 / (sign + n.z);
+    let b = n.x * n.y * a;
+    (Vec3::new(1.0 + sign * n.x * n.x * a, sign * b, -sign * n.x), Vec3::new(b, sign + n.y * n.y * a, -n.y))
+}
+
+// Dirección del hemisferio de `n` con densidad cos θ / π (rebote difuso), con `u` en [0,1)²
+pub fn cosine_hemisphere(n: Vec3, u: (f32, f32)) -> Vec3 {
+    let (dx, dy) = concentric_disk(u.0, u.1);
+    let dz = (1.0 - dx * dx - dy * dy).max(0.0).sqrt();
+    let (t, b) = orthonormal_basis(n);
+    (t * dx + b * dy + n * dz).normalize()
+}
+
+// [0,1)² -> polígono regular de `n` lados inscrito en el disco unidad (bokeh de diafragma con láminas).
+// `u` elige el triángulo (centro, vértice k, vértice k+1) y, reescalada, el punto dentro de él.
+pub fn regular_polygon(n: u32, u: f32, v: f32) -> (f32, f32) {
+    let n = n.max(3);
+    let k = ((u * n as f32) as u32).min(n - 1);
+    let u = u * n as f32 - k as f32;
+    let step = 2.0 * std::f32::consts::PI / n as f32;
+    let (a, b) = (k as f32 * step, (k + 1) as f32 * step);
+    let su = u.sqrt();
+    let (wa, wb) = (su * (1.0 - v), su * v);
+    (wa * a.cos() + wb * b.cos(), wa * a.sin() + wb * b.sin())
+}
@@ -1,58 +1,136 @@
 use crate::vec3::Vec3;
-use crate::texture::TextureKind;
-use crate::geometry::{Object, Cube};
+use crate::texture::{self, TextureKind, TextureAtlas};
+use crate::geometry::{Object, Cube, Sphere, Quad, Hit};
+use crate::bvh::Bvh;
+use crate::ray::Ray;
+use crate::skybox;
+use crate::light::Light;
+use crate::obj::ObjModel;
 
 #[derive(Clone)]
 pub struct Material {
-    pub name: &'static str,
+    pub name: String,
     pub texture: TextureKind,
-    pub albedo: f32,
+    pub albedo: f32,      // albedo/specular/shininess: solo modo Phong
     pub specular: f32,
     pub shininess: f32,
+    pub metallic: f32,    // 0 = dieléctrico, 1 = conductor (F0 = color base)
+    pub roughness: f32,   // rugosidad perceptual del GGX (α = roughness²)
     pub transparency: f32,
     pub reflectivity: f32,
     pub ior: f32,
+    pub emission: Vec3, // color * intensidad emitida (no depende de las luces)
+    pub absorption: Vec3, // coeficiente de absorción por unidad de distancia dentro del material (Beer–Lambert)
 }
 
+impl Material {
+    pub fn is_emissive(&self) -> bool { self.emission.x + self.emission.y + self.emission.z > 0.0 }
+}
+
+// Modelos OBJ del diorama: (archivo en la carpeta de assets, posición, rotación Y).
+// La escena no lee archivos: quien la arma los carga y pasa los que encontró
+pub const PROPS: [(&str, Vec3, f32); 3] = [
+    ("tree.obj",    Vec3 { x: -2.0, y: -0.37, z: 0.5 },  0.0),
+    ("tree.obj",    Vec3 { x: 2.0,  y: -0.37, z: -0.5 }, 1.3),
+    ("obelisk.obj", Vec3 { x: -1.8, y: -0.5,  z: -3.3 }, 0.4),
+];
+
+// Luz cálida de antorchas y faroles
+const LAMP_COLOR: Vec3 = Vec3 { x: 1.0, y: 0.72, z: 0.38 };
+const LAMP_MATERIAL: usize = 6;
+const LAMP_EMISSION: f32 = 1.2; // solo de noche, junto con las luces de las lámparas
+
 pub struct Scene {
     pub objects: Vec<Object>,
+    pub bvh: Bvh, // se construye una vez; llamar rebuild_bvh() si cambian los objetos
     pub materials: Vec<Material>,
-    pub light_pos: Vec3,
-    pub light_color: Vec3,
+    pub lights: Vec<Light>,
+    pub night_lights: Vec<Light>, // lámparas y focos: se encienden solo de noche
     pub ambient: f32,
     pub ambient_color: Vec3,
 }
 
 impl Scene {
+    pub fn rebuild_bvh(&mut self) { self.bvh = Bvh::build(&self.objects).with_stats(); }
+
+    pub fn hit(&self, r: &Ray, tmin: f32, tmax: f32) -> Option<Hit> { self.bvh.hit(&self.objects, r, tmin, tmax) }
+    // Los emisores no proyectan sombra: la luz de una lámpara está dentro de su propia geometría
+    pub fn occluded(&self, r: &Ray, tmin: f32, tmax: f32) -> bool {
+        self.bvh.occluded(&self.objects, r, tmin, tmax, |h| !self.materials[h.material_id].is_emissive())
+    }
+    // Fracción (por canal) de luz que llega a lo largo de un rayo de sombra: los materiales
+    // transparentes la atenúan según `transparency` y la tiñen con el tono de su textura;
+    // cualquier opaco la corta. Cada objeto atravesado cuenta una vez.
+    pub fn transmittance(&self, r: &Ray, tmin: f32, tmax: f32, atlas: Option<&TextureAtlas>) -> Vec3 {
+        let mut t = Vec3::ONE;
+        let blocked = self.bvh.occluded(&self.objects, r, tmin, tmax, |h| {
+            let m = &self.materials[h.material_id];
+            if m.is_emissive() { return false; }
+            if m.transparency <= 0.0 { return true; }
+            let c = texture::sample(m.texture, h.uv, h.p, atlas);
+            let tint = c * (1.0 / c.x.max(c.y).max(c.z).max(1e-3)); // solo el tono, sin oscurecer
+            t = t.hadamard(tint) * m.transparency;
+            t.x.max(t.y).max(t.z) < 1e-3
+        });
+        if blocked { Vec3::ZERO } else { t }
+    }
+
+    // Sin modelos OBJ
     pub fn diorama(angle_y: f32) -> Self {
-        Self::diorama_with_mode(angle_y, false)
+        Self::diorama_with_mode(angle_y, false, Vec::new())
     }
-    
-    pub fn diorama_with_mode(angle_y: f32, is_night: bool) -> Self {
+
+    // `props`: modelos ya cargados con su posición y rotación Y (ver `PROPS`)
+    pub fn diorama_with_mode(angle_y: f32, is_night: bool, props: Vec<(ObjModel, Vec3, f32)>) -> Self {
         // IDs esperados en el atlas:
-        // 0: grass, 1: dirt, 2: stone, 3: metal, 4: glass, 5: water, 6: lamp (vidrio luminoso)
-        let grass = Material { name:"Grass", texture:TextureKind::Image{ id:0 }, albedo:0.85, specular:0.15, shininess:32.0, transparency:0.0, reflectivity:0.0, ior:1.0 };
-        let dirt  = Material { name:"Dirt",  texture:TextureKind::Image{ id:1 }, albedo:0.9,  specular:0.05, shininess:8.0,  transparency:0.0, reflectivity:0.0, ior:1.0 };
-        let stone = Material { name:"Stone", texture:TextureKind::Image{ id:2 }, albedo:0.8,  specular:0.1,  shininess:16.0, transparency:0.0, reflectivity:0.0, ior:1.0 };
-        let metal = Material { name:"Metal", texture:TextureKind::Image{ id:3 }, albedo:0.1, specular:0.9, shininess:128.0, transparency:0.0, reflectivity:0.8, ior:1.0 };
-        let glass = Material { name:"Glass", texture:TextureKind::Image{ id:4 }, albedo:0.05, specular:0.9, shininess:128.0, transparency:0.85, reflectivity:0.1, ior:1.52 };
-        let water = Material { name:"Water", texture:TextureKind::Image{ id:5 }, albedo:0.2, specular:0.5, shininess:64.0, transparency:0.7, reflectivity:0.15, ior:1.33 };
-        let lamp  = Material { name:"Lamp",  texture:TextureKind::Image{ id:6 }, albedo:0.3, specular:0.8, shininess:96.0, transparency:0.35, reflectivity:0.2, ior:1.52 };
+        // 0: grass, 1: dirt, 2: stone, 3: metal, 4: glass, 5: water
+        // El material 6 (lamp) no usa el atlas: color sólido cálido, emisivo solo de noche (`set_night`).
+        // El 7 (frosted) es el vidrio con rugosidad: refracción esmerilada.
+        // Parámetros PBR: metallic/roughness/ior; albedo/specular/shininess/reflectivity son del modo Phong.
+        // Los dieléctricos opacos usan ior 1.5 (F0 ≈ 0.04).
+        // `absorption` oscurece y tiñe según la distancia recorrida dentro (agua: absorbe más el rojo).
+        let grass = Material { name:"Grass".into(), texture:TextureKind::Image{ id:0 }, albedo:0.85, specular:0.15, shininess:32.0, metallic:0.0, roughness:0.9,  transparency:0.0, reflectivity:0.0, ior:1.5, emission:Vec3::ZERO, absorption:Vec3::ZERO };
+        let dirt  = Material { name:"Dirt".into(),  texture:TextureKind::Image{ id:1 }, albedo:0.9,  specular:0.05, shininess:8.0,  metallic:0.0, roughness:0.95, transparency:0.0, reflectivity:0.0, ior:1.5, emission:Vec3::ZERO, absorption:Vec3::ZERO };
+        let stone = Material { name:"Stone".into(), texture:TextureKind::Image{ id:2 }, albedo:0.8,  specular:0.1,  shininess:16.0, metallic:0.0, roughness:0.8,  transparency:0.0, reflectivity:0.0, ior:1.5, emission:Vec3::ZERO, absorption:Vec3::ZERO };
+        let metal = Material { name:"Metal".into(), texture:TextureKind::Image{ id:3 }, albedo:0.1, specular:0.9, shininess:128.0, metallic:1.0, roughness:0.25, transparency:0.0, reflectivity:0.8, ior:1.0, emission:Vec3::ZERO, absorption:Vec3::ZERO };
+        let glass = Material { name:"Glass".into(), texture:TextureKind::Image{ id:4 }, albedo:0.05, specular:0.9, shininess:128.0, metallic:0.0, roughness:0.0,  transparency:0.85, reflectivity:0.1, ior:1.52, emission:Vec3::ZERO, absorption:Vec3::new(0.4, 0.1, 0.3) };
+        let water = Material { name:"Water".into(), texture:TextureKind::Image{ id:5 }, albedo:0.2, specular:0.5, shininess:64.0, metallic:0.0, roughness:0.08, transparency:0.7, reflectivity:0.15, ior:1.33, emission:Vec3::ZERO, absorption:Vec3::new(4.0, 1.5, 0.6) };
+        let lamp  = Material { name:"Lamp".into(),  texture:TextureKind::Solid(LAMP_COLOR), albedo:0.3, specular:0.8, shininess:96.0, metallic:0.0, roughness:0.7, transparency:0.0, reflectivity:0.0, ior:1.52, emission:Vec3::ZERO, absorption:Vec3::ZERO };
+        let frost = Material { name:"Frosted".into(), texture:TextureKind::Image{ id:4 }, albedo:0.05, specular:0.6, shininess:32.0, metallic:0.0, roughness:0.35, transparency:0.85, reflectivity:0.1, ior:1.52, emission:Vec3::ZERO, absorption:Vec3::new(0.4, 0.1, 0.3) };
 
-        let mats = vec![grass, dirt, stone, metal, glass, water, lamp];
+        let mut mats = vec![grass, dirt, stone, metal, glass, water, lamp, frost];
 
         let mut objs: Vec<Object> = vec![];
         
         // ====== SUELO BASE (terreno con grass y dirt) ======
+        // Tapa superior en y=-0.5: por fila de celdas, tramos contiguos del mismo material
+        // (grass dentro del círculo r²<12, dirt fuera). UV en unidades de mundo.
+        let top = -0.5;
         for z in -4..=4 {
-            for x in -4..=4 {
-                let r = (x*x + z*z) as f32;
-                let mat = if r < 12.0 { 0 } else { 1 }; // grass dentro, dirt fuera
-                let center = Vec3::new(x as f32, -0.75, z as f32);
-                objs.push(Object::Cube(Cube::new(center, Vec3::new(0.5,0.25,0.5), 0.0, mat)));
+            let mut x0 = -4;
+            while x0 <= 4 {
+                let mat = |x: i32| if ((x*x + z*z) as f32) < 12.0 { 0 } else { 1 };
+                let m = mat(x0);
+                let mut x1 = x0;
+                while x1 < 4 && mat(x1 + 1) == m { x1 += 1; }
+                let corner = Vec3::new(x0 as f32 - 0.5, top, z as f32 - 0.5);
+                let len = (x1 - x0 + 1) as f32;
+                objs.extend(Quad::from_corner(corner, Vec3::new(len,0.0,0.0), Vec3::new(0.0,0.0,1.0), m).map(Object::Quad));
+                x0 = x1 + 1;
             }
         }
-        
+        // Laterales de tierra (0.5 de alto)
+        let (e, h) = (4.5, 0.5);
+        let sides = [
+            (Vec3::new(-e, top-h,  e), Vec3::new( 2.0*e, 0.0, 0.0)),
+            (Vec3::new( e, top-h, -e), Vec3::new(-2.0*e, 0.0, 0.0)),
+            (Vec3::new( e, top-h,  e), Vec3::new(0.0, 0.0, -2.0*e)),
+            (Vec3::new(-e, top-h, -e), Vec3::new(0.0, 0.0,  2.0*e)),
+        ];
+        for (corner, edge) in sides {
+            objs.extend(Quad::from_corner(corner, edge, Vec3::new(0.0,h,0.0), 1).map(Object::Quad));
+        }
+
         // ====== CASTILLO CENTRAL (piedra) ======
         // Torre principal (centro)
         for i in 0..5 {
@@ -91,23 +169,41 @@ impl Scene {
         for i in 0..4 {
             objs.push(Object::Cube(Cube::new(Vec3::new(-3.0, -0.3 + i as f32 * 0.4, 1.0), Vec3::new(0.4, 0.4, 0.6), 0.0, 2)));
         }
-        // Ventanas de vidrio
+        // Ventanas de vidrio (la de abajo transparente, la de arriba esmerilada)
         objs.push(Object::Cube(Cube::new(Vec3::new(-2.6, 0.3, 1.0), Vec3::new(0.08, 0.25, 0.4), 0.0, 4)));
-        objs.push(Object::Cube(Cube::new(Vec3::new(-2.6, 0.9, 1.0), Vec3::new(0.08, 0.25, 0.4), 0.0, 4)));
+        objs.push(Object::Cube(Cube::new(Vec3::new(-2.6, 0.9, 1.0), Vec3::new(0.08, 0.25, 0.4), 0.0, 7)));
         
         // Edificio derecho (piedra con decoración metálica)
         for i in 0..3 {
             objs.push(Object::Cube(Cube::new(Vec3::new(3.0, -0.3 + i as f32 * 0.4, -1.0), Vec3::new(0.5, 0.4, 0.5), 0.0, 2)));
         }
-        // Decoración metálica en techo
-        objs.push(Object::Cube(Cube::new(Vec3::new(3.0, 0.9, -1.0), Vec3::new(0.3, 0.1, 0.3), 0.0, 3)));
+        // Techo metálico a dos aguas (dos láminas inclinadas 35° sobre Z)
+        let pitch = 35f32.to_radians();
+        for side in [-1.0f32, 1.0] {
+            let (run, rise) = (0.32 * pitch.cos(), 0.32 * pitch.sin());
+            let tr = crate::transform::Transform::rigid(
+                crate::transform::Mat3::rotate_z(-side * pitch),
+                Vec3::new(3.0 + side * run, 0.9 + rise, -1.0),
+            );
+            objs.push(Object::Cube(Cube::with_transform(tr, Vec3::new(0.34, 0.03, 0.56), 3)));
+        }
         
         // ====== PUENTE Y FUENTE DE AGUA ======
-        // Fuente de agua (centro-sur)
-        objs.push(Object::Cube(Cube::new(Vec3::new(0.0, -0.4, 2.5), Vec3::new(0.6, 0.15, 0.6), 0.0, 2))); // borde
-        objs.push(Object::Cube(Cube::new(Vec3::new(0.0, -0.35, 2.5), Vec3::new(0.5, 0.12, 0.5), 0.0, 5))); // agua
+        // Fuente de agua (centro-sur): pileta hueca con un escalón poco profundo junto al borde
+        // y el centro hondo, para que la absorción del agua se note según la profundidad
+        let fc = Vec3::new(0.0, 0.0, 2.5);
+        objs.push(Object::Cube(Cube::new(fc + Vec3::new(0.0, -0.3775, 0.0), Vec3::new(0.505, 0.1075, 0.505), 0.0, 5))); // agua
+        for s in [-1.0f32, 1.0] {
+            // borde (y -0.5..-0.25); el agua se mete 5 mm en la piedra para no tener caras coplanares
+            objs.push(Object::Cube(Cube::new(fc + Vec3::new(s * 0.55, -0.375, 0.0), Vec3::new(0.05, 0.125, 0.6), 0.0, 2)));
+            objs.push(Object::Cube(Cube::new(fc + Vec3::new(0.0, -0.375, s * 0.55), Vec3::new(0.5, 0.125, 0.05), 0.0, 2)));
+            // escalón interior (6 cm bajo la superficie)
+            objs.push(Object::Cube(Cube::new(fc + Vec3::new(s * 0.44, -0.415, 0.0), Vec3::new(0.06, 0.085, 0.38), 0.0, 2)));
+            objs.push(Object::Cube(Cube::new(fc + Vec3::new(0.0, -0.415, s * 0.44), Vec3::new(0.5, 0.085, 0.06), 0.0, 2)));
+        }
+        objs.push(Object::Cube(Cube::new(fc + Vec3::new(0.0, -0.49, 0.0), Vec3::new(0.5, 0.01, 0.5), 0.0, 2))); // fondo
         // Pilar central de la fuente
-        objs.push(Object::Cube(Cube::new(Vec3::new(0.0, -0.1, 2.5), Vec3::new(0.1, 0.2, 0.1), 0.0, 2)));
+        objs.push(Object::Cube(Cube::new(Vec3::new(0.0, -0.19, 2.5), Vec3::new(0.1, 0.29, 0.1), 0.0, 2)));
         objs.push(Object::Cube(Cube::new(Vec3::new(0.0, 0.1, 2.5), Vec3::new(0.15, 0.05, 0.15), 0.0, 3)));
         
         // Puente de piedra (norte)
@@ -121,12 +217,12 @@ impl Scene {
         
         // ====== DECORACIONES ======
         // Esferas metálicas decorativas
-        objs.push(Object::Cube(Cube::new(Vec3::new(-2.5, -0.35, -2.0), Vec3::new(0.18, 0.18, 0.18), 0.0, 3)));
-        objs.push(Object::Cube(Cube::new(Vec3::new(2.5, -0.35, 2.0), Vec3::new(0.18, 0.18, 0.18), 0.0, 3)));
+        objs.push(Object::Sphere(Sphere::new(Vec3::new(-2.5, -0.35, -2.0), 0.18, 3)));
+        objs.push(Object::Sphere(Sphere::new(Vec3::new(2.5, -0.35, 2.0), 0.18, 3)));
         
-        // Cristales decorativos (pequeños) - usa material lamp (ID 6) para que se vean mejor
-        objs.push(Object::Cube(Cube::new(Vec3::new(-1.0, -0.3, -0.8), Vec3::new(0.12, 0.2, 0.12), 0.3, 6)));
-        objs.push(Object::Cube(Cube::new(Vec3::new(1.0, -0.3, 0.8), Vec3::new(0.12, 0.2, 0.12), -0.3, 6)));
+        // Cristales decorativos (pequeños), de vidrio: sin luz propia
+        objs.push(Object::Cube(Cube::new(Vec3::new(-1.0, -0.3, -0.8), Vec3::new(0.12, 0.2, 0.12), 0.3, 4)));
+        objs.push(Object::Cube(Cube::new(Vec3::new(1.0, -0.3, 0.8), Vec3::new(0.12, 0.2, 0.12), -0.3, 4)));
         
         // Pilares ornamentales (esquinas exteriores)
         let pillars = [
@@ -153,7 +249,16 @@ impl Scene {
         // Estanque pequeño adicional
         objs.push(Object::Cube(Cube::new(Vec3::new(-3.0, -0.45, -1.5), Vec3::new(0.4, 0.08, 0.4), 0.0, 5)));
 
+        // ====== PROPS (mallas OBJ en assets/) ======
+        // Árboles sobre los jardines y un obelisco junto al camino
+        for (model, pos, rot_y) in props {
+            let tr = crate::transform::Transform::rigid(crate::transform::Mat3::rotate_y(rot_y), pos);
+            objs.push(Object::Mesh(model.into_mesh(tr, &mut mats)));
+        }
+
         // ====== ANTORCHAS/LÁMPARAS (para iluminación nocturna) ======
+        // Cada lámpara es un cubo emisivo (material 6) con una esfera de luz inscrita
+        let mut lamps: Vec<(Vec3, f32)> = vec![];
         // Antorchas en las torres esquineras
         let torch_positions = [
             Vec3::new(-1.5, 1.2, -1.5),
@@ -164,60 +269,84 @@ impl Scene {
         for &pos in &torch_positions {
             // Poste de metal
             objs.push(Object::Cube(Cube::new(pos + Vec3::new(0.0, -0.15, 0.0), Vec3::new(0.05, 0.15, 0.05), 0.0, 3)));
-            // Lámpara de vidrio (emite luz) - usa material lamp (ID 6)
             objs.push(Object::Cube(Cube::new(pos, Vec3::new(0.1, 0.1, 0.1), 0.0, 6)));
+            lamps.push((pos, 0.1));
         }
         
         // Lámparas en la torre principal
         for i in 0..4 {
             let angle = i as f32 * std::f32::consts::PI / 2.0;
-            let x = angle.cos() * 0.5;
-            let z = angle.sin() * 0.5;
-            objs.push(Object::Cube(Cube::new(Vec3::new(x, 1.3, z), Vec3::new(0.08, 0.08, 0.08), 0.0, 6)));
+            let pos = Vec3::new(angle.cos() * 0.5, 1.3, angle.sin() * 0.5);
+            objs.push(Object::Cube(Cube::new(pos, Vec3::new(0.08, 0.08, 0.08), 0.0, 6)));
+            lamps.push((pos, 0.08));
         }
         
         // Faroles en el camino
-        objs.push(Object::Cube(Cube::new(Vec3::new(-0.6, -0.2, -2.5), Vec3::new(0.06, 0.06, 0.06), 0.0, 6)));
-        objs.push(Object::Cube(Cube::new(Vec3::new(0.6, -0.2, -2.5), Vec3::new(0.06, 0.06, 0.06), 0.0, 6)));
+        for pos in [Vec3::new(-0.6, -0.2, -2.5), Vec3::new(0.6, -0.2, -2.5)] {
+            objs.push(Object::Cube(Cube::new(pos, Vec3::new(0.06, 0.06, 0.06), 0.0, 6)));
+            lamps.push((pos, 0.06));
+        }
 
-        // Rotación global (match sin warning)
+        // Rotación global (se compone con la orientación propia de cada objeto)
         let rot = crate::transform::Mat3::rotate_y(angle_y);
+        let world = crate::transform::Transform::rigid(rot, Vec3::ZERO);
         for o in objs.iter_mut() {
-            match o {
-                crate::geometry::Object::Cube(c) => {
-                    let p = c.tr.pos;
-                    let rotated = rot.mul_vec3(p);
-                    c.tr = crate::transform::Transform::new(rot, rotated);
-                }
-            }
+            let tr = o.transform_mut();
+            *tr = world.compose(tr);
+        }
+
+        // Una esfera de luz por lámpara (sombras suaves) y un foco desde la corona de la torre hacia la fuente
+        let mut night_lights: Vec<Light> = lamps.iter().map(|&(p, r)| Light::sphere(rot.mul_vec3(p), r, LAMP_COLOR, 2.2, 3.0)).collect();
+        let (spot_pos, spot_target) = (Vec3::new(0.0, 1.8, 0.4), Vec3::new(0.0, -0.35, 2.5));
+        night_lights.push(Light::spot(rot.mul_vec3(spot_pos), rot.mul_vec3(spot_target - spot_pos), Vec3::new(0.85, 0.9, 1.0),
+                                      3.0, 6.0, 16f32.to_radians(), 6f32.to_radians()));
+        // Luz cálida saliendo por las ventanas del edificio izquierdo (rectángulos hacia +X)
+        for y in [0.3, 0.9] {
+            let corner = Vec3::new(-2.51, y - 0.25, 1.4);
+            night_lights.push(Light::rect(rot.mul_vec3(corner), rot.mul_vec3(Vec3::new(0.0, 0.0, -0.8)), rot.mul_vec3(Vec3::new(0.0, 0.5, 0.0)),
+                                          LAMP_COLOR, 1.2, 3.5));
         }
 
-        // Configuración de iluminación según el modo
-        let (light_pos, light_color, ambient, ambient_color) = if is_night {
+        let mut sc = Self {
+            bvh: Bvh::build(&objs).with_stats(),
+            objects: objs,
+            materials: mats,
+            lights: vec![],
+            night_lights,
+            ambient: 0.0,
+            ambient_color: Vec3::ZERO,
+        };
+        sc.set_night(is_night);
+        sc
+    }
+
+    // Solo cambia la iluminación: la geometría (y el BVH) no dependen del modo
+    pub fn set_night(&mut self, is_night: bool) {
+        // Configuración de iluminación según el modo.
+        // Sol y luna son luces direccionales con la misma dirección que su disco en el skybox.
+        let (sky_light, ambient, ambient_color) = if is_night {
             // MODO NOCTURNO: Luz lunar azulada desde arriba
             (
-                Vec3::new(-8.0, 12.0, 5.0),           // Luna alta y lejana
-                Vec3::new(0.6, 0.7, 1.0),             // Luz azulada fría
+                Light::directional(skybox::moon_dir(), Vec3::new(0.6, 0.7, 1.0), 1.0), // Luz azulada fría
                 0.08,                                  // Ambient muy bajo
                 Vec3::new(0.15, 0.20, 0.35)           // Ambient azulado oscuro
             )
         } else {
             // MODO DÍA: Sol cálido
             (
-                Vec3::new(5.0, 6.0, -3.0),            // Sol
-                Vec3::new(1.0, 0.95, 0.85),           // Luz cálida amarillenta
+                Light::directional(skybox::sun_dir(), Vec3::new(1.0, 0.95, 0.85), 1.0), // Luz cálida amarillenta
                 0.15,                                  // Ambient normal
                 Vec3::new(1.0, 1.0, 1.0)              // Ambient neutro
             )
         };
 
-        Self {
-            objects: objs,
-            materials: mats,
-            light_pos,
-            light_color,
-            ambient,
-            ambient_color,
+        self.lights = vec![sky_light];
+        if is_night { self.lights.extend_from_slice(&self.night_lights); }
+        // Las lámparas brillan solo cuando sus luces están encendidas
+        if let Some(lamp) = self.materials.get_mut(LAMP_MATERIAL) {
+            lamp.emission = if is_night { LAMP_COLOR * LAMP_EMISSION } else { Vec3::ZERO };
         }
+        self.ambient = ambient;
+        self.ambient_color = ambient_color;
     }
 }
@@ -9,11 +9,21 @@ fn hash(n: u32) -> f32 {
     (x as f32) / (u32::MAX as f32)
 }
 
+// Direcciones (hacia el astro) del disco del sol y de la luna; la luz direccional
+// de la escena usa las mismas para que sombras y cielo coincidan
+pub fn sun_dir() -> Vec3 { Vec3::new(0.3, 0.6, -0.7).normalize() }
+pub fn moon_dir() -> Vec3 { Vec3::new(-0.4, 0.7, 0.3).normalize() }
+
 pub fn sample(dir: Vec3) -> Vec3 {
     sample_with_mode(dir, false)
 }
 
-pub fn sample_with_mode(dir: Vec3, is_night: bool) -> Vec3 {
+pub fn sample_with_mode(dir: Vec3, is_night: bool) -> Vec3 { sky(dir, is_night, true) }
+
+// Cielo sin el halo del sol/luna: para rebotes que ya reciben el astro por su luz direccional
+pub fn sample_without_glow(dir: Vec3, is_night: bool) -> Vec3 { sky(dir, is_night, false) }
+
+fn sky(dir: Vec3, is_night: bool, glow: bool) -> Vec3 {
     if is_night {
         // MODO NOCTURNO
         let t = (dir.y*0.5 + 0.5).clamp(0.0,1.0);
@@ -22,10 +32,10 @@ pub fn sample_with_mode(dir: Vec3, is_night: bool) -> Vec3 {
         let mut base = bottom*(1.0 - t) + top*t;
 
         // Luna (más grande y brillante)
-        let moon_dir = Vec3::new(-0.4, 0.7, 0.3).normalize();
+        let moon_dir = moon_dir();
         let dot_moon = dir.normalize().dot(moon_dir).max(0.0);
         let moon_glow = dot_moon.powf(150.0) * 2.5 + dot_moon.powf(800.0) * 5.0;
-        base = base + Vec3::new(0.9, 0.95, 1.0) * moon_glow;
+        if glow { base = base + Vec3::new(0.9, 0.95, 1.0) * moon_glow; }
 
         // Estrellas (solo en la parte superior del cielo)
         if dir.y > 0.1 {
@@ -49,9 +59,9 @@ pub fn sample_with_mode(dir: Vec3, is_night: bool) -> Vec3 {
         let bottom = Vec3::new(0.85, 0.93, 1.0);
         let base = bottom*(1.0 - t) + top*t;
 
-        let sun_dir = Vec3::new(0.3, 0.6, -0.7).normalize();
+        let sun_dir = sun_dir();
         let dot = dir.normalize().dot(sun_dir).max(0.0);
-        let glow = dot.powf(100.0) * 3.0 + dot.powf(1000.0) * 7.0;
-        (base + Vec3::new(1.0,0.9,0.7)*glow).clamp01()
+        let sun_glow = if glow { dot.powf(100.0) * 3.0 + dot.powf(1000.0) * 7.0 } else { 0.0 };
+        (base + Vec3::new(1.0,0.9,0.7)*sun_glow).clamp01()
     }
 }
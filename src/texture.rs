@@ -1,9 +1,10 @@
 use crate::vec3::Vec3;
-use raylib::prelude::*;
+use crate::imageio;
 
 #[derive(Copy, Clone)]
 pub enum TextureKind {
     Image { id: usize },                       // imagen externa del atlas
+    Solid(Vec3),                               // color constante (p.ej. Kd de un .mtl)
     Checker { a: Vec3, b: Vec3, scale: f32 }, // procedurales (fallback)
     Grass, Dirt, Stone, Metal, GlassTint, Water,
 }
@@ -28,6 +29,22 @@ impl CpuImage {
     }
 }
 
+// Texturas del diorama; el índice es el id de TextureKind::Image
+pub const ATLAS_PATHS: [&str; 6] = [
+    "assets/grass.png", // id 0
+    "assets/dirt.png",  // id 1
+    "assets/stone.png", // id 2
+    "assets/metal2.png", // id 3
+    "assets/glass.png", // id 4
+    "assets/water.png", // id 5
+];
+
+// Id en el atlas para una textura referenciada por archivo (p.ej. map_Kd), por nombre de archivo
+pub fn atlas_id(path: &str) -> Option<usize> {
+    let name = std::path::Path::new(path).file_name()?;
+    ATLAS_PATHS.iter().position(|p| std::path::Path::new(p).file_name() == Some(name))
+}
+
 pub struct TextureAtlas {
     pub images: Vec<CpuImage>,
 }
@@ -36,21 +53,14 @@ impl TextureAtlas {
     pub fn load_from_paths(paths: &[&str]) -> Self {
         let mut images = Vec::new();
         for p in paths {
-            match Image::load_image(p) {
-                Ok(img) => {
-                    let w = img.width();
-                    let h = img.height();
-                    // Nota: get_image_data() devuelve ImageColors; usamos .iter()
-                    let data = img.get_image_data();
-                    let mut rgba = Vec::with_capacity((w*h*4) as usize);
-                    for c in data.iter() {
-                        rgba.push(c.r); rgba.push(c.g); rgba.push(c.b); rgba.push(c.a);
-                    }
+            match imageio::read_png_rgba(p) {
+                Ok((w, h, rgba)) => {
+                    let (w, h) = (w as i32, h as i32);
                     images.push(CpuImage { w, h, rgba });
                     eprintln!("Cargada textura '{}': {}x{}", p, w, h);
                 }
-                Err(_) => {
-                    eprintln!("(aviso) No se pudo cargar '{}'. Usaré fallback procedural.", p);
+                Err(e) => {
+                    eprintln!("(aviso) No se pudo cargar '{}' ({}). Usaré fallback procedural.", p, e);
                 }
             }
         }
@@ -81,6 +91,7 @@ pub fn sample(kind: TextureKind, uv: (f32, f32), p: Vec3, atlas: Option<&Texture
             // Fallback si no hay imagen: magenta
             return Vec3::new(1.0, 0.0, 1.0);
         }
+        TextureKind::Solid(c) => c,
         TextureKind::Checker{a,b,scale} => {
             let s = ((u*scale).floor() as i32 + (v*scale).floor() as i32) & 1;
             if s==0 { a } else { b }
@@ -1,9 +1,23 @@
 use crate::vec3::Vec3;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct Mat3 { pub c0: Vec3, pub c1: Vec3, pub c2: Vec3 }
 
 impl Mat3 {
+    pub const IDENTITY: Mat3 = Mat3 {
+        c0: Vec3 { x: 1.0, y: 0.0, z: 0.0 },
+        c1: Vec3 { x: 0.0, y: 1.0, z: 0.0 },
+        c2: Vec3 { x: 0.0, y: 0.0, z: 1.0 },
+    };
+
+    pub fn rotate_x(theta: f32) -> Self {
+        let (s, c) = theta.sin_cos();
+        Self {
+            c0: Vec3::new(1.0,0.0,0.0),
+            c1: Vec3::new(0.0,c,s),
+            c2: Vec3::new(0.0,-s,c),
+        }
+    }
     pub fn rotate_y(theta: f32) -> Self {
         let (s, c) = theta.sin_cos();
         Self {
@@ -12,6 +26,30 @@ impl Mat3 {
             c2: Vec3::new(s,0.0,c),
         }
     }
+    pub fn rotate_z(theta: f32) -> Self {
+        let (s, c) = theta.sin_cos();
+        Self {
+            c0: Vec3::new(c,s,0.0),
+            c1: Vec3::new(-s,c,0.0),
+            c2: Vec3::new(0.0,0.0,1.0),
+        }
+    }
+    // Rotación de `theta` alrededor de un eje arbitrario (Rodrigues)
+    pub fn rotate_axis(axis: Vec3, theta: f32) -> Self {
+        Quat::from_axis_angle(axis, theta).to_mat3()
+    }
+    // Euler en radianes: primero X, luego Y, luego Z (M = Rz * Ry * Rx)
+    pub fn from_euler_xyz(x: f32, y: f32, z: f32) -> Self {
+        Self::rotate_z(z).mul_mat3(&Self::rotate_y(y)).mul_mat3(&Self::rotate_x(x))
+    }
+    pub fn scale(s: Vec3) -> Self {
+        Self {
+            c0: Vec3::new(s.x,0.0,0.0),
+            c1: Vec3::new(0.0,s.y,0.0),
+            c2: Vec3::new(0.0,0.0,s.z),
+        }
+    }
+
     pub fn mul_vec3(&self, v: Vec3) -> Vec3 {
         Vec3::new(
             self.c0.x*v.x + self.c1.x*v.y + self.c2.x*v.z,
@@ -19,6 +57,9 @@ impl Mat3 {
             self.c0.z*v.x + self.c1.z*v.y + self.c2.z*v.z,
         )
     }
+    pub fn mul_mat3(&self, o: &Mat3) -> Self {
+        Self { c0: self.mul_vec3(o.c0), c1: self.mul_vec3(o.c1), c2: self.mul_vec3(o.c2) }
+    }
     pub fn transpose(&self) -> Self {
         Self {
             c0: Vec3::new(self.c0.x, self.c1.x, self.c2.x),
@@ -26,19 +67,142 @@ impl Mat3 {
             c2: Vec3::new(self.c0.z, self.c1.z, self.c2.z),
         }
     }
+    pub fn determinant(&self) -> f32 { self.c0.dot(self.c1.cross(self.c2)) }
+    // Inversa por cofactores; None si es singular (o tiene NaN)
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if !(det.abs() >= 1e-12 && det.is_finite()) { return None; }
+        let inv_det = 1.0 / det;
+        // Las filas de la inversa son los productos cruz de las columnas
+        let r0 = self.c1.cross(self.c2) * inv_det;
+        let r1 = self.c2.cross(self.c0) * inv_det;
+        let r2 = self.c0.cross(self.c1) * inv_det;
+        Some(Self { c0: r0, c1: r1, c2: r2 }.transpose())
+    }
+    // Fila i (para bounds de esferas transformadas)
+    pub fn row(&self, i: usize) -> Vec3 { Vec3::new(self.c0.axis(i), self.c1.axis(i), self.c2.axis(i)) }
+}
+
+// Cuaternión unitario para orientaciones (w + xi + yj + zk)
+#[derive(Copy, Clone, Debug)]
+pub struct Quat { pub w: f32, pub x: f32, pub y: f32, pub z: f32 }
+
+impl Quat {
+    pub const IDENTITY: Quat = Quat { w: 1.0, x: 0.0, y: 0.0, z: 0.0 };
+
+    pub fn from_axis_angle(axis: Vec3, theta: f32) -> Self {
+        let a = axis.normalize();
+        let (s, c) = (theta * 0.5).sin_cos();
+        Self { w: c, x: a.x*s, y: a.y*s, z: a.z*s }
+    }
+    pub fn from_euler_xyz(x: f32, y: f32, z: f32) -> Self {
+        let qx = Self::from_axis_angle(Vec3::new(1.0,0.0,0.0), x);
+        let qy = Self::from_axis_angle(Vec3::new(0.0,1.0,0.0), y);
+        let qz = Self::from_axis_angle(Vec3::new(0.0,0.0,1.0), z);
+        qz.mul_quat(qy).mul_quat(qx)
+    }
+    // Composición: aplicar primero `o`, luego `self`
+    pub fn mul_quat(self, o: Quat) -> Self {
+        Self {
+            w: self.w*o.w - self.x*o.x - self.y*o.y - self.z*o.z,
+            x: self.w*o.x + self.x*o.w + self.y*o.z - self.z*o.y,
+            y: self.w*o.y - self.x*o.z + self.y*o.w + self.z*o.x,
+            z: self.w*o.z + self.x*o.y - self.y*o.x + self.z*o.w,
+        }
+    }
+    pub fn normalize(self) -> Self {
+        let l = (self.w*self.w + self.x*self.x + self.y*self.y + self.z*self.z).sqrt();
+        if l > 0.0 { Self { w: self.w/l, x: self.x/l, y: self.y/l, z: self.z/l } } else { Self::IDENTITY }
+    }
+    pub fn to_mat3(self) -> Mat3 {
+        let Quat { w, x, y, z } = self.normalize();
+        Mat3 {
+            c0: Vec3::new(1.0 - 2.0*(y*y + z*z), 2.0*(x*y + w*z), 2.0*(x*z - w*y)),
+            c1: Vec3::new(2.0*(x*y - w*z), 1.0 - 2.0*(x*x + z*z), 2.0*(y*z + w*x)),
+            c2: Vec3::new(2.0*(x*z + w*y), 2.0*(y*z - w*x), 1.0 - 2.0*(x*x + y*y)),
+        }
+    }
 }
 
-#[derive(Copy, Clone)]
+// Transformación afín local -> mundo: p_mundo = m * p_local + pos.
+// `m` puede incluir rotación arbitraria y escala no uniforme; las normales
+// usan la inversa transpuesta.
+#[derive(Copy, Clone, Debug)]
 pub struct Transform {
-    pub rot: Mat3,
-    pub inv_rot: Mat3,
+    pub m: Mat3,
+    pub inv: Mat3,
+    pub normal_m: Mat3,
     pub pos: Vec3,
 }
 
 impl Transform {
-    pub fn new(rot: Mat3, pos: Vec3) -> Self { Self { inv_rot: rot.transpose(), rot, pos } }
-    pub fn world_to_local_point(&self, p: Vec3) -> Vec3 { self.inv_rot.mul_vec3(p - self.pos) }
-    pub fn world_to_local_dir(&self, d: Vec3) -> Vec3 { self.inv_rot.mul_vec3(d) }
-    pub fn local_to_world_point(&self, p: Vec3) -> Vec3 { self.pos + self.rot.mul_vec3(p) }
-    pub fn local_to_world_dir(&self, d: Vec3) -> Vec3 { self.rot.mul_vec3(d) }
+    // None si `m` es singular (escala 0, aristas o normales nulas)
+    pub fn new(m: Mat3, pos: Vec3) -> Option<Self> {
+        let inv = m.inverse()?;
+        Some(Self { m, inv, normal_m: inv.transpose(), pos })
+    }
+    // Solo rotación: la inversa es la transpuesta
+    pub fn rigid(rot: Mat3, pos: Vec3) -> Self { Self { m: rot, inv: rot.transpose(), normal_m: rot, pos } }
+    pub fn identity() -> Self { Self::rigid(Mat3::IDENTITY, Vec3::ZERO) }
+    pub fn translate(pos: Vec3) -> Self { Self::rigid(Mat3::IDENTITY, pos) }
+    // Escala, luego rotación, luego traslación
+    pub fn from_trs(pos: Vec3, rot: Quat, scale: Vec3) -> Option<Self> {
+        Self::new(rot.to_mat3().mul_mat3(&Mat3::scale(scale)), pos)
+    }
+    // self ∘ o: se aplica primero `o` y luego `self`
+    pub fn compose(&self, o: &Transform) -> Self {
+        let inv = o.inv.mul_mat3(&self.inv);
+        Self { m: self.m.mul_mat3(&o.m), inv, normal_m: inv.transpose(), pos: self.local_to_world_point(o.pos) }
+    }
+
+    // Las direcciones no se renormalizan: t es el mismo en local y en mundo
+    pub fn world_to_local_point(&self, p: Vec3) -> Vec3 { self.inv.mul_vec3(p - self.pos) }
+    pub fn world_to_local_dir(&self, d: Vec3) -> Vec3 { self.inv.mul_vec3(d) }
+    pub fn local_to_world_point(&self, p: Vec3) -> Vec3 { self.pos + self.m.mul_vec3(p) }
+    pub fn local_to_world_dir(&self, d: Vec3) -> Vec3 { self.m.mul_vec3(d) }
+    pub fn local_to_world_normal(&self, n: Vec3) -> Vec3 { self.normal_m.mul_vec3(n).normalize() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &Mat3, b: &Mat3) -> bool {
+        (0..3).all(|i| (a.row(i) - b.row(i)).length() < 1e-5)
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        let m = Quat::from_euler_xyz(0.3, -1.1, 2.0).to_mat3().mul_mat3(&Mat3::scale(Vec3::new(0.5, 2.0, 3.0)));
+        let inv = m.inverse().unwrap();
+        assert!(close(&m.mul_mat3(&inv), &Mat3::IDENTITY));
+        assert!(close(&inv.mul_mat3(&m), &Mat3::IDENTITY));
+        assert!(Mat3::scale(Vec3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+
+    #[test]
+    fn quat_matches_matrix_rotations() {
+        let axes = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)];
+        let mats = [Mat3::rotate_x(0.7), Mat3::rotate_y(0.7), Mat3::rotate_z(0.7)];
+        for (a, m) in axes.iter().zip(&mats) {
+            assert!(close(&Quat::from_axis_angle(*a, 0.7).to_mat3(), m));
+        }
+        let (x, y, z) = (0.4, -0.9, 1.7);
+        assert!(close(&Quat::from_euler_xyz(x, y, z).to_mat3(), &Mat3::from_euler_xyz(x, y, z)));
+        // Una rotación es ortonormal: su inversa es la transpuesta
+        let r = Mat3::from_euler_xyz(x, y, z);
+        assert!(close(&r.inverse().unwrap(), &r.transpose()));
+    }
+
+    #[test]
+    fn transform_round_trip_and_singular() {
+        let tr = Transform::from_trs(Vec3::new(1.0, -2.0, 0.5), Quat::from_euler_xyz(0.2, 0.5, -0.3), Vec3::new(2.0, 0.5, 1.5)).unwrap();
+        let p = Vec3::new(0.3, 4.0, -1.2);
+        assert!((tr.world_to_local_point(tr.local_to_world_point(p)) - p).length() < 1e-5);
+        let both = Transform::rigid(Mat3::rotate_y(1.0), Vec3::new(0.0, 1.0, 0.0)).compose(&tr);
+        assert!(close(&both.m.mul_mat3(&both.inv), &Mat3::IDENTITY));
+        assert!(Transform::from_trs(Vec3::ZERO, Quat::IDENTITY, Vec3::new(1.0, 1e-7, 1e-7)).is_none());
+        assert!(crate::geometry::Plane::new(Vec3::ZERO, Vec3::ZERO, 0).is_none());
+        assert!(crate::geometry::Quad::from_corner(Vec3::ZERO, Vec3::ZERO, Vec3::new(0.0, 0.0, 1.0), 0).is_none());
+    }
 }
@@ -1,4 +1,4 @@
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Vec3 { pub x: f32, pub y: f32, pub z: f32 }
 
 impl Vec3 {
@@ -14,6 +14,9 @@ impl Vec3 {
     pub fn cross(self, o: Vec3) -> Self {
         Self::new(self.y*o.z - self.z*o.y, self.z*o.x - self.x*o.z, self.x*o.y - self.y*o.x)
     }
+    pub fn min(self, o: Vec3) -> Self { Self::new(self.x.min(o.x), self.y.min(o.y), self.z.min(o.z)) }
+    pub fn max(self, o: Vec3) -> Self { Self::new(self.x.max(o.x), self.y.max(o.y), self.z.max(o.z)) }
+    pub fn axis(self, i: usize) -> f32 { match i { 0 => self.x, 1 => self.y, _ => self.z } }
     pub fn length(self) -> f32 { self.dot(self).sqrt() }
     pub fn normalize(self) -> Self {
         let l = self.length(); if l>0.0 { self.mul(1.0/l) } else { self }
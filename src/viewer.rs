@@ -0,0 +1,152 @@
+use raylib::prelude::*;
+use raylib::ffi; // UpdateTexture
+
+use diorama::{scene, Bokeh, Vec3, CameraMode, ControlInput};
+use diorama::render::{render_progressive, depth_to_rgba, autofocus, Accumulator, Shading, Integrator};
+
+use crate::cli;
+
+// Actualiza la textura vía FFI (robusto entre versiones)
+fn update_texture_rgba(tex: &mut Texture2D, pixels: &[u8]) {
+    unsafe {
+        ffi::UpdateTexture(*tex.as_ref(), pixels.as_ptr() as *const std::ffi::c_void);
+    }
+}
+
+pub fn run(opts: &cli::Options) {
+    let width = opts.width as i32;
+    let height = opts.height as i32;
+
+    let (mut rl, thread) = raylib::init()
+        .size(width, height)
+        .title("Diorama Raytracer (Rust + raylib)")
+        .build();
+
+    rl.set_target_fps(30);
+
+    let atlas = crate::load_atlas();
+    let mut settings = opts.render_settings();
+
+    // Buffers
+    let mut pixels = vec![0u8; (width*height*4) as usize];
+    let mut depthbuf = vec![f32::INFINITY; (width*height) as usize];
+    let mut accum = Accumulator::new(width as u32, height as u32);
+
+    // Texture destino
+    let blank = Image::gen_image_color(width, height, Color::BLACK);
+    let mut tex = rl.load_texture_from_image(&thread, &blank).unwrap();
+
+    // Estado
+    let mut ctrl = crate::initial_controller(opts);
+    let mut fov: f32 = opts.fov;
+    let aspect = width as f32 / height as f32;
+    let mut auto_rotate = true;
+    let mut show_depth = false;
+    let mut is_night = opts.is_night;
+    // Lente: foco fijo al mover la cámara hasta que se reenfoque
+    let mut aperture = opts.aperture;
+    let mut focus_dist = opts.focus.unwrap_or(ctrl.dist);
+    let mut bokeh = opts.bokeh;
+    let mut projection = opts.projection;
+
+    // La escena se construye una sola vez; girar es mover la cámara
+    let mut sc = scene::Scene::diorama_with_mode(0.0, is_night, crate::load_props());
+    let mut last_view = None;
+
+    while !rl.window_should_close() {
+        // input de la cámara: arrastre izquierdo gira/mira, derecho desplaza (órbita), rueda acerca
+        let key = |k: KeyboardKey| if rl.is_key_down(k) { 1.0 } else { 0.0 };
+        let drag = rl.get_mouse_delta();
+        let left = rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT);
+        let right = rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_RIGHT);
+        let input = ControlInput {
+            look: if left { (drag.x, drag.y) } else { (0.0, 0.0) },
+            pan: if right { (drag.x, drag.y) } else { (0.0, 0.0) },
+            zoom: rl.get_mouse_wheel_move(),
+            walk: Vec3::new(key(KeyboardKey::KEY_D) - key(KeyboardKey::KEY_A),
+                            key(KeyboardKey::KEY_SPACE) - key(KeyboardKey::KEY_LEFT_CONTROL),
+                            key(KeyboardKey::KEY_W) - key(KeyboardKey::KEY_S)),
+            dt: rl.get_frame_time(),
+            fast: rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT),
+        };
+        if rl.is_key_pressed(KeyboardKey::KEY_C) { ctrl.toggle_mode(); }
+        if rl.is_key_pressed(KeyboardKey::KEY_R) { auto_rotate = !auto_rotate; }
+        if rl.is_key_pressed(KeyboardKey::KEY_Z) { show_depth = !show_depth; }
+        if rl.is_key_pressed(KeyboardKey::KEY_N) { is_night = !is_night; sc.set_night(is_night); }  // Toggle día/noche
+        if rl.is_key_pressed(KeyboardKey::KEY_Q) { fov = (fov-1.0).clamp(25.0, 90.0); }
+        if rl.is_key_pressed(KeyboardKey::KEY_E) { fov = (fov+1.0).clamp(25.0, 90.0); }
+        if rl.is_key_pressed(KeyboardKey::KEY_M) {
+            settings.shading = if settings.shading == Shading::Pbr { Shading::Phong } else { Shading::Pbr };
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_T) {
+            settings.integrator = if settings.integrator == Integrator::Whitted { Integrator::PathTracer } else { Integrator::Whitted };
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F) { settings.filter = settings.filter.next(); }
+        if rl.is_key_down(KeyboardKey::KEY_LEFT_BRACKET) { focus_dist = (focus_dist - 0.05).max(0.1); }
+        if rl.is_key_down(KeyboardKey::KEY_RIGHT_BRACKET) { focus_dist += 0.05; }
+        if rl.is_key_pressed(KeyboardKey::KEY_MINUS) { aperture = (aperture - 0.02).max(0.0); }
+        if rl.is_key_pressed(KeyboardKey::KEY_EQUAL) { aperture = (aperture + 0.02).min(0.5); }
+        if rl.is_key_pressed(KeyboardKey::KEY_B) {
+            bokeh = match bokeh { Bokeh::Disk => Bokeh::Polygon(5), Bokeh::Polygon(5) => Bokeh::Polygon(6), _ => Bokeh::Disk };
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_O) { projection = projection.next(); }
+        if rl.is_key_pressed(KeyboardKey::KEY_V) { settings.stereo = settings.stereo.next(); }
+        if rl.is_key_pressed(KeyboardKey::KEY_P) { rl.take_screenshot(&thread, "out/frame.png"); } // <- con thread
+
+        if auto_rotate && ctrl.mode == CameraMode::Orbit { ctrl.yaw += 0.01; }
+        ctrl.update(&input);
+
+        // cámara
+        let mut cam = ctrl.camera(fov, aspect);
+        cam.aperture = aperture;
+        cam.focus_dist = focus_dist;
+        cam.bokeh = bokeh;
+        cam.projection = projection;
+        cam.fisheye_deg = opts.fisheye_deg;
+        if let Some(d) = opts.iod { cam.iod = d; }
+        if let Some(d) = opts.convergence { cam.convergence = d; }
+        if let Some(h) = opts.view_height { cam.view_height = h; }
+        // Autofoco en el centro de la pantalla, con la cámara mono aunque se vea en estéreo
+        if rl.is_key_pressed(KeyboardKey::KEY_G) && let Some(f) = autofocus(&cam, &sc, width as u32, height as u32) {
+            focus_dist = f;
+            cam.focus_dist = f;
+        }
+
+        // render progresivo: con la cámara quieta se promedian los frames; cualquier cambio reinicia
+        let view = (ctrl, fov, is_night, settings.shading, settings.integrator, settings.filter, aperture, focus_dist, bokeh, projection, settings.stereo);
+        if last_view != Some(view) { accum.reset(); last_view = Some(view); }
+        settings.frame = settings.frame.wrapping_add(1);
+        render_progressive(width as u32, height as u32, &cam, &sc, &mut accum, &mut pixels, &mut depthbuf, Some(&atlas), is_night, &settings);
+        if show_depth {
+            let mut tmp = vec![0u8; pixels.len()];
+            depth_to_rgba(&depthbuf, 0.1, 50.0, &mut tmp);
+            update_texture_rgba(&mut tex, &tmp);
+        } else {
+            update_texture_rgba(&mut tex, &pixels);
+        }
+
+        // draw
+        let mut d = rl.begin_drawing(&thread);
+        d.clear_background(Color::BLACK);
+        d.draw_texture(&tex, 0, 0, Color::WHITE);
+        d.draw_text(
+            &format!("C {} | R auto:{} | Z depth:{} | N {}| Q/E FOV:{:.0} | M {} | T {} spp:{} | F {} | P screenshot",
+                     if ctrl.mode == CameraMode::Orbit {"ÓRBITA (arrastrar, A/D, W/S, rueda)"} else {"VUELO (WASD, espacio/ctrl, arrastrar)"},
+                     if auto_rotate {"ON"} else {"OFF"},
+                     if show_depth {"ON"} else {"OFF"},
+                     if is_night {"NOCHE"} else {"DÍA"},
+                     fov,
+                     if settings.shading == Shading::Pbr {"GGX"} else {"PHONG"},
+                     if settings.integrator == Integrator::PathTracer {"PATH"} else {"WHITTED"},
+                     accum.frames * settings.samples.max(1),
+                     settings.filter.name()),
+            10, 10, 18, Color::WHITE
+        );
+        d.draw_text(
+            &format!("O {} | V {} | [ ] foco:{:.2} | -/= apertura:{:.2} | B bokeh:{} | G autofoco",
+                     projection.name(), settings.stereo.name(), focus_dist, aperture,
+                     match bokeh { Bokeh::Disk => "disco".to_string(), Bokeh::Polygon(n) => format!("{} láminas", n) }),
+            10, 32, 18, Color::WHITE
+        );
+    }
+}